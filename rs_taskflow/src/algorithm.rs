@@ -0,0 +1,433 @@
+use std::cmp::Ordering;
+use std::ops::Range;
+use std::sync::Arc;
+
+use crate::flow::{Flow, TaskHandle};
+use crate::task::*;
+
+type PieceFuncType<T, P> = Arc<dyn Fn(&[T]) -> P + Send + Sync>;
+type GatherFuncType<P, O> = Arc<dyn Fn(Vec<&P>) -> O + Send + Sync>;
+
+//
+// Decides how the elements of the input collection are split across the chunk tasks of an
+// algorithm subgraph.
+//
+// The number of chunk tasks is fixed when the subgraph is inserted into the flow. By default
+// every chunk task gets one contiguous block of roughly `len / num_tasks` elements; with a
+// chunk size the input is cut into pieces of that size which are dealt out round-robin.
+//
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Partitioner {
+    num_tasks: usize,
+    chunk_size: Option<usize>,
+}
+
+impl Partitioner {
+    pub fn new(num_tasks: usize) -> Self {
+        assert!(num_tasks > 0, "a partitioner needs at least one task");
+        Self {
+            num_tasks,
+            chunk_size: None,
+        }
+    }
+
+    pub fn with_chunk_size(self, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk size must be positive");
+        Self {
+            num_tasks: self.num_tasks,
+            chunk_size: Some(chunk_size),
+        }
+    }
+
+    pub fn get_num_tasks(&self) -> usize {
+        self.num_tasks
+    }
+
+    pub fn get_chunk_size(&self) -> Option<usize> {
+        self.chunk_size
+    }
+
+    pub fn get_pieces(&self, len: usize, task_index: usize) -> Vec<Range<usize>> {
+        match self.chunk_size {
+            None => {
+                let start = task_index * len / self.num_tasks;
+                let end = (task_index + 1) * len / self.num_tasks;
+                (start < end).then_some(start..end).into_iter().collect()
+            }
+            Some(chunk_size) => (task_index * chunk_size..len)
+                .step_by(self.num_tasks * chunk_size)
+                .map(|start| start..usize::min(start + chunk_size, len))
+                .collect(),
+        }
+    }
+}
+
+impl Default for Partitioner {
+    fn default() -> Self {
+        Self::new(thread_count())
+    }
+}
+
+fn thread_count() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+//
+// Entry point of an algorithm subgraph. Hands its upstream input on to the chunk tasks without
+// copying the collection.
+//
+#[derive(Clone)]
+pub struct ScatterTask<T> {
+    input_handle: Option<TaskInputHandle<Vec<T>>>,
+    output: Option<TaskInputHandle<Vec<T>>>,
+}
+
+impl<T: 'static + Clone + Send + Sync> TaskInput0<Vec<T>> for ScatterTask<T> {
+    fn set_input_0(&mut self, task_input: TaskInputHandle<Vec<T>>) {
+        self.input_handle = Some(task_input);
+    }
+}
+
+impl<T: 'static + Clone + Send + Sync> TaskOutput0<TaskInputHandle<Vec<T>>> for ScatterTask<T> {
+    fn get_output_0(task: &dyn ExecutableTask) -> Option<&TaskInputHandle<Vec<T>>> {
+        task.as_any()
+            .downcast_ref::<Self>()
+            .unwrap()
+            .output
+            .as_ref()
+    }
+}
+
+impl<T: 'static + Clone + Send + Sync> ExecutableTask for ScatterTask<T> {
+    fn exec(&mut self, _flow: &Flow) {
+        match &self.input_handle {
+            Some(input) => {
+                self.output = Some(input.clone());
+            }
+            _ => panic!("input 0 is not connected"),
+        }
+    }
+}
+
+//
+// Applies the per-piece function to the pieces of the input assigned to one chunk task.
+//
+#[derive(Clone)]
+pub struct ChunkTask<T, P> {
+    task_index: usize,
+    partitioner: Partitioner,
+    input_handle: Option<TaskInputHandle<TaskInputHandle<Vec<T>>>>,
+    output: Option<Vec<(usize, P)>>,
+    func: PieceFuncType<T, P>,
+}
+
+impl<T: 'static + Clone + Send + Sync, P: 'static + Clone + Send + Sync>
+    TaskInput0<TaskInputHandle<Vec<T>>> for ChunkTask<T, P>
+{
+    fn set_input_0(&mut self, task_input: TaskInputHandle<TaskInputHandle<Vec<T>>>) {
+        self.input_handle = Some(task_input);
+    }
+}
+
+impl<T: 'static + Clone + Send + Sync, P: 'static + Clone + Send + Sync>
+    TaskOutput0<Vec<(usize, P)>> for ChunkTask<T, P>
+{
+    fn get_output_0(task: &dyn ExecutableTask) -> Option<&Vec<(usize, P)>> {
+        task.as_any()
+            .downcast_ref::<Self>()
+            .unwrap()
+            .output
+            .as_ref()
+    }
+}
+
+impl<T: 'static + Clone + Send + Sync, P: 'static + Clone + Send + Sync> ExecutableTask
+    for ChunkTask<T, P>
+{
+    fn exec(&mut self, flow: &Flow) {
+        match &self.input_handle {
+            Some(input) => {
+                let values = input.get_value(flow).unwrap().get_value(flow).unwrap();
+                let pieces = self
+                    .partitioner
+                    .get_pieces(values.len(), self.task_index)
+                    .into_iter()
+                    .map(|range| (range.start, (self.func)(&values[range])))
+                    .collect();
+                self.output = Some(pieces);
+            }
+            _ => panic!("input 0 is not connected"),
+        }
+    }
+}
+
+//
+// Exit point of an algorithm subgraph. Collects the pieces of every chunk task connected to it
+// and combines them in input order.
+//
+#[derive(Clone)]
+pub struct GatherTask<P, O> {
+    input_handles: Vec<TaskInputHandle<Vec<(usize, P)>>>,
+    output: Option<O>,
+    func: GatherFuncType<P, O>,
+}
+
+impl<P: 'static + Clone + Send + Sync, O: 'static + Clone + Send + Sync> TaskInput0<Vec<(usize, P)>>
+    for GatherTask<P, O>
+{
+    fn set_input_0(&mut self, task_input: TaskInputHandle<Vec<(usize, P)>>) {
+        self.input_handles.push(task_input);
+    }
+}
+
+impl<P: 'static + Clone + Send + Sync, O: 'static + Clone + Send + Sync> TaskOutput0<O>
+    for GatherTask<P, O>
+{
+    fn get_output_0(task: &dyn ExecutableTask) -> Option<&O> {
+        task.as_any()
+            .downcast_ref::<Self>()
+            .unwrap()
+            .output
+            .as_ref()
+    }
+}
+
+impl<P: 'static + Clone + Send + Sync, O: 'static + Clone + Send + Sync> ExecutableTask
+    for GatherTask<P, O>
+{
+    fn exec(&mut self, flow: &Flow) {
+        let mut pieces: Vec<&(usize, P)> = self
+            .input_handles
+            .iter()
+            .flat_map(|input| input.get_value(flow).unwrap().iter())
+            .collect();
+        pieces.sort_unstable_by_key(|(start, _)| *start);
+        self.output = Some((self.func)(pieces.into_iter().map(|(_, p)| p).collect()));
+    }
+
+    fn get_input_join_policy(&self, _port: usize) -> JoinPolicy {
+        JoinPolicy::Collect
+    }
+}
+
+pub struct Subgraph<A, B> {
+    pub entry: TaskHandle<A>,
+    pub exit: TaskHandle<B>,
+}
+
+pub type AlgorithmSubgraph<T, P, O> = Subgraph<ScatterTask<T>, GatherTask<P, O>>;
+
+fn insert_partitioned<
+    T: 'static + Clone + Send + Sync,
+    P: 'static + Clone + Send + Sync,
+    O: 'static + Clone + Send + Sync,
+>(
+    flow: &mut Flow,
+    label: &str,
+    partitioner: Partitioner,
+    piece_func: PieceFuncType<T, P>,
+    gather_func: GatherFuncType<P, O>,
+) -> AlgorithmSubgraph<T, P, O> {
+    let cluster = flow.add_cluster(label);
+    let scatter = flow.add_new_task(ScatterTask {
+        input_handle: None,
+        output: None,
+    });
+    let gather = flow.add_new_task(GatherTask {
+        input_handles: Vec::new(),
+        output: None,
+        func: gather_func,
+    });
+    flow.set_task_name(&scatter, "scatter");
+    flow.set_task_cluster(&scatter, cluster);
+    flow.set_task_name(&gather, "gather");
+    flow.set_task_cluster(&gather, cluster);
+
+    for task_index in 0..partitioner.get_num_tasks() {
+        let chunk = flow.add_new_task(ChunkTask {
+            task_index,
+            partitioner,
+            input_handle: None,
+            output: None,
+            func: piece_func.clone(),
+        });
+        flow.set_task_name(&chunk, &format!("chunk {}", task_index));
+        flow.set_task_cluster(&chunk, cluster);
+        flow.connect_output0_to_input0(&scatter, &chunk);
+        flow.connect_output0_to_input0(&chunk, &gather);
+    }
+
+    Subgraph {
+        entry: scatter,
+        exit: gather,
+    }
+}
+
+// Calls `func` on every element of the input collection; the exit task outputs `()` once all
+// elements have been visited.
+pub fn for_each<T: 'static + Clone + Send + Sync, F: 'static + Send + Sync + Fn(&T)>(
+    flow: &mut Flow,
+    partitioner: Partitioner,
+    func: F,
+) -> AlgorithmSubgraph<T, (), ()> {
+    insert_partitioned(
+        flow,
+        "for_each",
+        partitioner,
+        Arc::new(move |piece: &[T]| piece.iter().for_each(&func)),
+        Arc::new(|_pieces: Vec<&()>| ()),
+    )
+}
+
+// Maps every element of the input collection through `func`, preserving order.
+pub fn transform<
+    T: 'static + Clone + Send + Sync,
+    U: 'static + Clone + Send + Sync,
+    F: 'static + Send + Sync + Fn(&T) -> U,
+>(
+    flow: &mut Flow,
+    partitioner: Partitioner,
+    func: F,
+) -> AlgorithmSubgraph<T, Vec<U>, Vec<U>> {
+    insert_partitioned(
+        flow,
+        "transform",
+        partitioner,
+        Arc::new(move |piece: &[T]| piece.iter().map(&func).collect()),
+        Arc::new(|pieces: Vec<&Vec<U>>| pieces.into_iter().flatten().cloned().collect()),
+    )
+}
+
+// Folds the input collection into `init` with `op`. Pieces are combined in input order, so
+// `op` only has to be associative.
+pub fn reduce<T: 'static + Clone + Send + Sync, F: 'static + Send + Sync + Fn(&T, &T) -> T>(
+    flow: &mut Flow,
+    partitioner: Partitioner,
+    init: T,
+    op: F,
+) -> AlgorithmSubgraph<T, T, T> {
+    let op = Arc::new(op);
+    let gather_op = op.clone();
+    insert_partitioned(
+        flow,
+        "reduce",
+        partitioner,
+        Arc::new(move |piece: &[T]| {
+            piece[1..]
+                .iter()
+                .fold(piece[0].clone(), |acc, x| op(&acc, x))
+        }),
+        Arc::new(move |pieces: Vec<&T>| {
+            pieces
+                .into_iter()
+                .fold(init.clone(), |acc, x| gather_op(&acc, x))
+        }),
+    )
+}
+
+// Computes the inclusive prefix scan of the input collection with the associative `op`.
+pub fn inclusive_scan<
+    T: 'static + Clone + Send + Sync,
+    F: 'static + Send + Sync + Fn(&T, &T) -> T,
+>(
+    flow: &mut Flow,
+    partitioner: Partitioner,
+    op: F,
+) -> AlgorithmSubgraph<T, Vec<T>, Vec<T>> {
+    let op = Arc::new(op);
+    let gather_op = op.clone();
+    insert_partitioned(
+        flow,
+        "inclusive_scan",
+        partitioner,
+        Arc::new(move |piece: &[T]| {
+            let mut result = Vec::with_capacity(piece.len());
+            for x in piece {
+                let next = match result.last() {
+                    Some(acc) => op(acc, x),
+                    None => x.clone(),
+                };
+                result.push(next);
+            }
+            result
+        }),
+        Arc::new(move |pieces: Vec<&Vec<T>>| {
+            let mut result: Vec<T> = Vec::new();
+            for piece in pieces {
+                match result.last().cloned() {
+                    Some(carry) => result.extend(piece.iter().map(|x| gather_op(&carry, x))),
+                    None => result.extend(piece.iter().cloned()),
+                }
+            }
+            result
+        }),
+    )
+}
+
+// Sorts the input collection with the comparator `cmp`. Every piece is sorted by its chunk task
+// and the sorted pieces are merged by the exit task.
+pub fn sort_by<
+    T: 'static + Clone + Send + Sync,
+    F: 'static + Send + Sync + Fn(&T, &T) -> Ordering,
+>(
+    flow: &mut Flow,
+    partitioner: Partitioner,
+    cmp: F,
+) -> AlgorithmSubgraph<T, Vec<T>, Vec<T>> {
+    let cmp = Arc::new(cmp);
+    let gather_cmp = cmp.clone();
+    insert_partitioned(
+        flow,
+        "sort",
+        partitioner,
+        Arc::new(move |piece: &[T]| {
+            let mut sorted = piece.to_vec();
+            sorted.sort_by(|a, b| cmp(a, b));
+            sorted
+        }),
+        Arc::new(move |pieces: Vec<&Vec<T>>| {
+            let mut runs: Vec<Vec<T>> = pieces.into_iter().cloned().collect();
+            while runs.len() > 1 {
+                let mut merged_runs = Vec::with_capacity(runs.len().div_ceil(2));
+                let mut runs_iter = runs.into_iter();
+                while let Some(a) = runs_iter.next() {
+                    match runs_iter.next() {
+                        Some(b) => merged_runs.push(merge(a, b, gather_cmp.as_ref())),
+                        None => merged_runs.push(a),
+                    }
+                }
+                runs = merged_runs;
+            }
+            runs.pop().unwrap_or_default()
+        }),
+    )
+}
+
+pub fn sort<T: 'static + Clone + Send + Sync + Ord>(
+    flow: &mut Flow,
+    partitioner: Partitioner,
+) -> AlgorithmSubgraph<T, Vec<T>, Vec<T>> {
+    sort_by(flow, partitioner, T::cmp)
+}
+
+fn merge<T>(a: Vec<T>, b: Vec<T>, cmp: &dyn Fn(&T, &T) -> Ordering) -> Vec<T> {
+    let mut result = Vec::with_capacity(a.len() + b.len());
+    let mut a_iter = a.into_iter().peekable();
+    let mut b_iter = b.into_iter().peekable();
+    loop {
+        let take_a = match (a_iter.peek(), b_iter.peek()) {
+            (Some(x), Some(y)) => cmp(x, y) != Ordering::Greater,
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            (None, None) => break,
+        };
+        if take_a {
+            result.push(a_iter.next().unwrap());
+        } else {
+            result.push(b_iter.next().unwrap());
+        }
+    }
+    result
+}
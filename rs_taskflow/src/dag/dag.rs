@@ -1,16 +1,19 @@
 // Based on https://github.com/bunker-inspector/rs_taskflow/tree/master/src/flow/dag
 
 use std::cmp::Eq;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 use crate::dag::node::{Node, NodeId};
+#[allow(deprecated)]
 use crate::dag::visit::DagVisitationInfo;
 
 pub struct Dag<T: Eq + Clone> {
     nodes: Vec<RwLock<Node<T>>>,
-    dependencies: Vec<HashSet<NodeId>>, // upstream nodes
-    dependants: Vec<HashSet<NodeId>>,   // downstream nodes
+    dependencies: Vec<HashSet<NodeId>>,        // upstream nodes
+    dependants: Vec<HashSet<NodeId>>,          // downstream nodes
+    all_of_dependencies: Vec<HashSet<NodeId>>, // upstream nodes that must all complete
+    any_of_dependencies: Vec<HashMap<usize, HashSet<NodeId>>>, // groups of upstream nodes of which one must complete
 }
 
 impl<T: Eq + Clone> Dag<T> {
@@ -19,6 +22,8 @@ impl<T: Eq + Clone> Dag<T> {
             nodes: Vec::new(),
             dependencies: Vec::new(),
             dependants: Vec::new(),
+            all_of_dependencies: Vec::new(),
+            any_of_dependencies: Vec::new(),
         }
     }
 
@@ -27,12 +32,57 @@ impl<T: Eq + Clone> Dag<T> {
         self.nodes.push(RwLock::new(Node::new(id, value)));
         self.dependencies.push(HashSet::new());
         self.dependants.push(HashSet::new());
+        self.all_of_dependencies.push(HashSet::new());
+        self.any_of_dependencies.push(HashMap::new());
         id
     }
 
     pub fn connect(&mut self, from_node_id: NodeId, to_node_id: NodeId) {
         self.dependencies[to_node_id].insert(from_node_id); // dependencies are upstream
         self.dependants[from_node_id].insert(to_node_id); // dependants are downstream
+        self.all_of_dependencies[to_node_id].insert(from_node_id);
+    }
+
+    // to_node only waits for the first of the nodes connected with the same group to complete
+    pub fn connect_any_of(&mut self, from_node_id: NodeId, to_node_id: NodeId, group: usize) {
+        self.dependencies[to_node_id].insert(from_node_id);
+        self.dependants[from_node_id].insert(to_node_id);
+        self.any_of_dependencies[to_node_id]
+            .entry(group)
+            .or_default()
+            .insert(from_node_id);
+    }
+
+    // undoes connect(), keeping the edge if from_node is still in one of to_node's any-of groups
+    pub fn disconnect(&mut self, from_node_id: NodeId, to_node_id: NodeId) {
+        self.all_of_dependencies[to_node_id].remove(&from_node_id);
+        if !self.any_of_dependencies[to_node_id]
+            .values()
+            .any(|group| group.contains(&from_node_id))
+        {
+            self.dependencies[to_node_id].remove(&from_node_id);
+            self.dependants[from_node_id].remove(&to_node_id);
+        }
+    }
+
+    pub fn is_ready<F: Fn(NodeId) -> bool>(&self, node_id: NodeId, is_completed: F) -> bool {
+        self.all_of_dependencies[node_id]
+            .iter()
+            .all(|dep_node_id| is_completed(*dep_node_id))
+            && self.any_of_dependencies[node_id]
+                .values()
+                .all(|group| group.iter().any(|dep_node_id| is_completed(*dep_node_id)))
+    }
+
+    // a node is blocked once a dependency it waits for has failed, or every node of one of its
+    // any-of groups has failed
+    pub fn is_blocked<F: Fn(NodeId) -> bool>(&self, node_id: NodeId, is_failed: F) -> bool {
+        self.all_of_dependencies[node_id]
+            .iter()
+            .any(|dep_node_id| is_failed(*dep_node_id))
+            || self.any_of_dependencies[node_id]
+                .values()
+                .any(|group| group.iter().all(|dep_node_id| is_failed(*dep_node_id)))
     }
 
     pub fn get_num_nodes(&self) -> usize {
@@ -40,11 +90,11 @@ impl<T: Eq + Clone> Dag<T> {
         self.nodes.len()
     }
 
-    pub fn get_node(&self, node_id: NodeId) -> RwLockReadGuard<Node<T>> {
+    pub fn get_node(&self, node_id: NodeId) -> RwLockReadGuard<'_, Node<T>> {
         self.nodes[node_id].read().unwrap()
     }
 
-    pub fn get_mut_node(&self, node_id: NodeId) -> RwLockWriteGuard<Node<T>> {
+    pub fn get_mut_node(&self, node_id: NodeId) -> RwLockWriteGuard<'_, Node<T>> {
         self.nodes[node_id].write().unwrap()
     }
 
@@ -60,8 +110,170 @@ impl<T: Eq + Clone> Dag<T> {
         &self.dependants[node_id]
     }
 
+    pub fn get_all_of_dependencies(&self, node_id: NodeId) -> &HashSet<NodeId> {
+        &self.all_of_dependencies[node_id]
+    }
+
+    pub fn get_any_of_dependencies(&self, node_id: NodeId) -> &HashMap<usize, HashSet<NodeId>> {
+        &self.any_of_dependencies[node_id]
+    }
+
+    //
+    // Groups the nodes into levels: the first level holds the nodes without dependencies and
+    // every other node sits one level below its deepest dependency. Nodes in the same level do
+    // not depend on each other and can run in parallel. Within a level nodes are ordered by id.
+    //
+    pub fn get_levels(&self) -> Result<Vec<Vec<NodeId>>, &'static str> {
+        let mut num_dependencies: Vec<usize> = self.dependencies.iter().map(|d| d.len()).collect();
+        let mut levels = Vec::new();
+        let mut level: Vec<NodeId> = (0..self.get_num_nodes())
+            .filter(|node_id| num_dependencies[*node_id] == 0)
+            .collect();
+        let mut num_visited = 0;
+
+        while !level.is_empty() {
+            num_visited += level.len();
+            let mut next_level = Vec::new();
+            for node_id in level.iter() {
+                for dep_node_id in self.dependants[*node_id].iter() {
+                    num_dependencies[*dep_node_id] -= 1;
+                    if num_dependencies[*dep_node_id] == 0 {
+                        next_level.push(*dep_node_id);
+                    }
+                }
+            }
+            next_level.sort_unstable();
+            levels.push(level);
+            level = next_level;
+        }
+
+        if num_visited == self.get_num_nodes() {
+            Ok(levels)
+        } else {
+            Err("Cycle detected. DAG is invalid!")
+        }
+    }
+
+    // every node comes after all of its dependencies
+    pub fn topological_sort(&self) -> Result<Vec<NodeId>, &'static str> {
+        Ok(self.get_levels()?.into_iter().flatten().collect())
+    }
+
+    // number of nodes on the longest chain of dependencies
+    pub fn get_depth(&self) -> Result<usize, &'static str> {
+        Ok(self.get_levels()?.len())
+    }
+
+    // largest number of nodes in one level
+    pub fn get_width(&self) -> Result<usize, &'static str> {
+        Ok(self
+            .get_levels()?
+            .iter()
+            .map(|level| level.len())
+            .max()
+            .unwrap_or(0))
+    }
+
+    // all nodes that `node_id` transitively depends on
+    pub fn ancestors(&self, node_id: NodeId) -> HashSet<NodeId> {
+        Self::collect_reachable(node_id, &self.dependencies)
+    }
+
+    // all nodes that transitively depend on `node_id`
+    pub fn descendants(&self, node_id: NodeId) -> HashSet<NodeId> {
+        Self::collect_reachable(node_id, &self.dependants)
+    }
+
+    // whether there is a path of one or more edges from `from_node_id` down to `to_node_id`
+    pub fn is_reachable(&self, from_node_id: NodeId, to_node_id: NodeId) -> bool {
+        let mut visited = HashSet::new();
+        let mut stack = vec![from_node_id];
+        while let Some(node_id) = stack.pop() {
+            for dep_node_id in self.dependants[node_id].iter() {
+                if *dep_node_id == to_node_id {
+                    return true;
+                }
+                if visited.insert(*dep_node_id) {
+                    stack.push(*dep_node_id);
+                }
+            }
+        }
+        false
+    }
+
+    fn collect_reachable(node_id: NodeId, edges: &[HashSet<NodeId>]) -> HashSet<NodeId> {
+        let mut visited = HashSet::new();
+        let mut stack = vec![node_id];
+        while let Some(node_id) = stack.pop() {
+            for next_node_id in edges[node_id].iter() {
+                if visited.insert(*next_node_id) {
+                    stack.push(*next_node_id);
+                }
+            }
+        }
+        visited
+    }
+
+    // edges (from, to) that are implied by a longer path from `from` to `to`, ordered by node id
+    pub fn get_redundant_edges(&self) -> Vec<(NodeId, NodeId)> {
+        let mut redundant_edges = Vec::new();
+        for (from_node_id, dependants) in self.dependants.iter().enumerate() {
+            for to_node_id in dependants.iter() {
+                if dependants.iter().any(|other_node_id| {
+                    other_node_id != to_node_id && self.is_reachable(*other_node_id, *to_node_id)
+                }) {
+                    redundant_edges.push((from_node_id, *to_node_id));
+                }
+            }
+        }
+        redundant_edges.sort_unstable();
+        redundant_edges
+    }
+
+    //
+    // Copy of the graph without its redundant edges, i.e. the smallest graph with the same
+    // reachability. Only the shape of the graph is kept: a node of the copy no longer waits for
+    // the producers whose edges were removed, so the copy is meant for analysis, not execution.
+    //
+    pub fn transitive_reduction(&self) -> Self {
+        let mut reduced = self.clone();
+        for (from_node_id, to_node_id) in self.get_redundant_edges() {
+            reduced.dependencies[to_node_id].remove(&from_node_id);
+            reduced.dependants[from_node_id].remove(&to_node_id);
+            reduced.all_of_dependencies[to_node_id].remove(&from_node_id);
+            for group in reduced.any_of_dependencies[to_node_id].values_mut() {
+                group.remove(&from_node_id);
+            }
+            reduced.any_of_dependencies[to_node_id].retain(|_, group| !group.is_empty());
+        }
+        reduced
+    }
+
+    // copy of the graph with every node value converted by `func`, connected the same way
+    pub fn try_map<U: Eq + Clone, E, F: FnMut(NodeId, &T) -> Result<U, E>>(
+        &self,
+        mut func: F,
+    ) -> Result<Dag<U>, E> {
+        let mut nodes = Vec::with_capacity(self.nodes.len());
+        for (node_id, node) in self.nodes.iter().enumerate() {
+            let value = func(node_id, node.read().unwrap().get_value())?;
+            nodes.push(RwLock::new(Node::new(node_id, value)));
+        }
+        Ok(Dag {
+            nodes,
+            dependencies: self.dependencies.clone(),
+            dependants: self.dependants.clone(),
+            all_of_dependencies: self.all_of_dependencies.clone(),
+            any_of_dependencies: self.any_of_dependencies.clone(),
+        })
+    }
+
     // find roots
-    pub fn build_bfs(&self) -> Result<DagVisitationInfo<T>, &str> {
+    #[deprecated(
+        note = "use `Dag::topological_sort()` or `Dag::get_levels()` to visit a dag in dependency order"
+    )]
+    #[allow(deprecated)]
+    pub fn build_bfs(&self) -> Result<DagVisitationInfo<'_, T>, &str> {
         let mut bfs = DagVisitationInfo::new(self);
 
         for (to_node_id, deps) in self.dependencies.iter().enumerate() {
@@ -95,6 +307,8 @@ impl<T: Eq + Clone> Clone for Dag<T> {
             nodes: Dag::copy_nodes(self),
             dependencies: self.dependencies.clone(),
             dependants: self.dependants.clone(),
+            all_of_dependencies: self.all_of_dependencies.clone(),
+            any_of_dependencies: self.any_of_dependencies.clone(),
         }
     }
 
@@ -102,6 +316,8 @@ impl<T: Eq + Clone> Clone for Dag<T> {
         self.nodes = Dag::copy_nodes(source);
         self.dependencies = source.dependencies.clone();
         self.dependants = source.dependants.clone();
+        self.all_of_dependencies = source.all_of_dependencies.clone();
+        self.any_of_dependencies = source.any_of_dependencies.clone();
     }
 }
 
@@ -111,11 +327,183 @@ impl<T: Eq + Clone> Default for Dag<T> {
     }
 }
 
+// two graphs are equal when their nodes are equal and connected the same way
+impl<T: Eq + Clone> PartialEq for Dag<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.get_num_nodes() == other.get_num_nodes()
+            && (0..self.get_num_nodes())
+                .all(|node_id| *self.get_node(node_id) == *other.get_node(node_id))
+            && self.all_of_dependencies == other.all_of_dependencies
+            && self.any_of_dependencies == other.any_of_dependencies
+    }
+}
+
+impl<T: Eq + Clone> Eq for Dag<T> {}
+
+//
+// Conversions to and from petgraph, so that its algorithms can run on the graph. Node `i` of the
+// dag becomes node index `i` of the petgraph graph, every edge points from the dependency to the
+// dependant and carries the any-of group of the connection, or `None` for an all-of connection.
+//
+#[cfg(feature = "petgraph")]
+impl<T: Eq + Clone> Dag<T> {
+    pub fn to_petgraph(&self) -> petgraph::Graph<T, Option<usize>> {
+        let mut graph = petgraph::Graph::with_capacity(self.get_num_nodes(), 0);
+        for node_id in 0..self.get_num_nodes() {
+            graph.add_node(self.get_node(node_id).get_value().clone());
+        }
+        for to_node_id in 0..self.get_num_nodes() {
+            let mut edges: Vec<(NodeId, Option<usize>)> = self.all_of_dependencies[to_node_id]
+                .iter()
+                .map(|from_node_id| (*from_node_id, None))
+                .collect();
+            for (group, members) in self.any_of_dependencies[to_node_id].iter() {
+                edges.extend(
+                    members
+                        .iter()
+                        .map(|from_node_id| (*from_node_id, Some(*group))),
+                );
+            }
+            edges.sort_unstable();
+            for (from_node_id, group) in edges {
+                graph.add_edge(
+                    petgraph::graph::NodeIndex::new(from_node_id),
+                    petgraph::graph::NodeIndex::new(to_node_id),
+                    group,
+                );
+            }
+        }
+        graph
+    }
+
+    pub fn from_petgraph(graph: petgraph::Graph<T, Option<usize>>) -> Result<Self, &'static str> {
+        let (nodes, edges) = graph.into_nodes_edges();
+        let mut dag = Dag::new();
+        for node in nodes {
+            dag.add_node(node.weight);
+        }
+        for edge in edges {
+            let (from_node_id, to_node_id) = (edge.source().index(), edge.target().index());
+            match edge.weight {
+                Some(group) => dag.connect_any_of(from_node_id, to_node_id, group),
+                None => dag.connect(from_node_id, to_node_id),
+            }
+        }
+        dag.get_levels()?;
+        Ok(dag)
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SerializedNode<T> {
+    id: NodeId,
+    value: T,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SerializedEdge {
+    from: NodeId,
+    to: NodeId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    any_of_group: Option<usize>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SerializedDag<T> {
+    nodes: Vec<SerializedNode<T>>,
+    edges: Vec<SerializedEdge>, // ordered by target node, group and source node
+}
+
+#[cfg(feature = "serde")]
+impl<T: Eq + Clone + serde::Serialize> serde::Serialize for Dag<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let nodes = (0..self.get_num_nodes())
+            .map(|node_id| SerializedNode {
+                id: node_id,
+                value: self.get_node(node_id).get_value().clone(),
+            })
+            .collect();
+
+        let mut edges = Vec::new();
+        for to_node_id in 0..self.get_num_nodes() {
+            let mut all_of: Vec<NodeId> = self.all_of_dependencies[to_node_id]
+                .iter()
+                .copied()
+                .collect();
+            all_of.sort_unstable();
+            edges.extend(all_of.into_iter().map(|from_node_id| SerializedEdge {
+                from: from_node_id,
+                to: to_node_id,
+                any_of_group: None,
+            }));
+
+            let mut groups: Vec<_> = self.any_of_dependencies[to_node_id].iter().collect();
+            groups.sort_unstable_by_key(|(group, _)| **group);
+            for (group, members) in groups {
+                let mut any_of: Vec<NodeId> = members.iter().copied().collect();
+                any_of.sort_unstable();
+                edges.extend(any_of.into_iter().map(|from_node_id| SerializedEdge {
+                    from: from_node_id,
+                    to: to_node_id,
+                    any_of_group: Some(*group),
+                }));
+            }
+        }
+
+        SerializedDag { nodes, edges }.serialize(serializer)
+    }
+}
+
+//
+// Nodes must be listed in id order starting from 0, as `Serialize` writes them, and the edges
+// must not form a cycle.
+//
+#[cfg(feature = "serde")]
+impl<'de, T: Eq + Clone + serde::Deserialize<'de>> serde::Deserialize<'de> for Dag<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let serialized = SerializedDag::<T>::deserialize(deserializer)?;
+        let mut dag = Dag::new();
+        for node in serialized.nodes {
+            if node.id != dag.get_num_nodes() {
+                return Err(D::Error::custom(format!(
+                    "expected node {}, found node {}",
+                    dag.get_num_nodes(),
+                    node.id
+                )));
+            }
+            dag.add_node(node.value);
+        }
+        for edge in serialized.edges {
+            if edge.from >= dag.get_num_nodes() || edge.to >= dag.get_num_nodes() {
+                return Err(D::Error::custom(format!(
+                    "edge {} -> {} refers to an unknown node",
+                    edge.from, edge.to
+                )));
+            }
+            match edge.any_of_group {
+                Some(group) => dag.connect_any_of(edge.from, edge.to, group),
+                None => dag.connect(edge.from, edge.to),
+            }
+        }
+        dag.get_levels().map_err(D::Error::custom)?;
+        Ok(dag)
+    }
+}
+
 #[cfg(test)]
+#[allow(deprecated)]
 mod tests {
+    use std::collections::HashSet;
+
     use crate::dag::Dag;
 
     #[derive(Hash, Clone, Eq, PartialEq, Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     struct MockStruct {
         id: char,
     }
@@ -177,6 +565,209 @@ mod tests {
         assert!(bfs.is_err());
     }
 
+    #[test]
+    fn ready_with_any_of_dependencies() {
+        let mut dag = Dag::new();
+
+        let a = dag.add_node(MockStruct::new('A'));
+        let b = dag.add_node(MockStruct::new('B'));
+        let c = dag.add_node(MockStruct::new('C'));
+        let d = dag.add_node(MockStruct::new('D'));
+
+        dag.connect(a, d);
+        dag.connect_any_of(b, d, 1);
+        dag.connect_any_of(c, d, 1);
+
+        assert_eq!(dag.get_dependencies(d).len(), 3);
+        assert!(!dag.is_ready(d, |_| false));
+        assert!(!dag.is_ready(d, |id| id == a));
+        assert!(!dag.is_ready(d, |id| id == b || id == c));
+        assert!(dag.is_ready(d, |id| id == a || id == c));
+        assert!(dag.is_ready(a, |_| false));
+
+        assert!(!dag.is_blocked(d, |_| false));
+        assert!(dag.is_blocked(d, |id| id == a));
+        assert!(!dag.is_blocked(d, |id| id == b));
+        assert!(dag.is_blocked(d, |id| id == b || id == c));
+    }
+
+    #[test]
+    fn disconnect() {
+        let mut dag = Dag::new();
+
+        let a = dag.add_node(MockStruct::new('A'));
+        let b = dag.add_node(MockStruct::new('B'));
+        let c = dag.add_node(MockStruct::new('C'));
+
+        dag.connect(a, c);
+        dag.connect(b, c);
+        dag.connect_any_of(b, c, 1);
+
+        dag.disconnect(a, c);
+        assert!(!dag.get_dependencies(c).contains(&a));
+        assert!(dag.get_dependants(a).is_empty());
+
+        // b is still in an any-of group of c
+        dag.disconnect(b, c);
+        assert!(!dag.get_all_of_dependencies(c).contains(&b));
+        assert!(dag.get_dependencies(c).contains(&b));
+        assert!(dag.get_dependants(b).contains(&c));
+    }
+
+    #[test]
+    fn topological_order_and_levels() {
+        let mut dag = Dag::new();
+
+        let a = dag.add_node(MockStruct::new('A'));
+        let b = dag.add_node(MockStruct::new('B'));
+        let c = dag.add_node(MockStruct::new('C'));
+        let d = dag.add_node(MockStruct::new('D'));
+        let e = dag.add_node(MockStruct::new('E'));
+        let f = dag.add_node(MockStruct::new('F'));
+
+        dag.connect(e, a);
+        dag.connect(a, b);
+        dag.connect(a, c);
+        dag.connect(b, d);
+        dag.connect(e, d);
+
+        assert_eq!(
+            dag.get_levels().unwrap(),
+            vec![vec![e, f], vec![a], vec![b, c], vec![d]]
+        );
+        assert_eq!(dag.topological_sort().unwrap(), vec![e, f, a, b, c, d]);
+        assert_eq!(dag.get_depth().unwrap(), 4);
+        assert_eq!(dag.get_width().unwrap(), 2);
+
+        assert_eq!(Dag::<MockStruct>::new().get_depth().unwrap(), 0);
+        assert_eq!(Dag::<MockStruct>::new().get_width().unwrap(), 0);
+
+        dag.connect(d, e);
+        assert!(dag.topological_sort().is_err());
+        assert!(dag.get_width().is_err());
+    }
+
+    #[test]
+    fn reachability_queries() {
+        let mut dag = Dag::new();
+
+        let a = dag.add_node(MockStruct::new('A'));
+        let b = dag.add_node(MockStruct::new('B'));
+        let c = dag.add_node(MockStruct::new('C'));
+        let d = dag.add_node(MockStruct::new('D'));
+        let e = dag.add_node(MockStruct::new('E'));
+
+        dag.connect(a, b);
+        dag.connect(b, c);
+        dag.connect(a, c);
+        dag.connect(c, d);
+        dag.connect(a, d);
+
+        assert_eq!(dag.ancestors(d), HashSet::from([a, b, c]));
+        assert_eq!(dag.ancestors(a), HashSet::new());
+        assert_eq!(dag.descendants(b), HashSet::from([c, d]));
+        assert!(dag.is_reachable(a, d));
+        assert!(!dag.is_reachable(d, a));
+        assert!(!dag.is_reachable(a, a));
+        assert!(!dag.is_reachable(a, e));
+
+        assert_eq!(dag.get_redundant_edges(), vec![(a, c), (a, d)]);
+        let reduced = dag.transitive_reduction();
+        assert!(reduced.get_redundant_edges().is_empty());
+        assert_eq!(reduced.get_dependants(a), &HashSet::from([b]));
+        assert_eq!(reduced.get_dependencies(d), &HashSet::from([c]));
+        assert!(reduced.is_ready(d, |id| id == c));
+        assert_eq!(reduced.descendants(a), dag.descendants(a));
+        assert_eq!(dag.get_dependants(a).len(), 3);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_dag() {
+        let mut dag = Dag::new();
+
+        let a = dag.add_node(MockStruct::new('A'));
+        let b = dag.add_node(MockStruct::new('B'));
+        let c = dag.add_node(MockStruct::new('C'));
+        let d = dag.add_node(MockStruct::new('D'));
+
+        dag.connect(a, b);
+        dag.connect_any_of(a, c, 0);
+        dag.connect_any_of(b, c, 0);
+        dag.connect(c, d);
+
+        let json = serde_json::to_string(&dag).unwrap();
+        assert_eq!(
+            json,
+            "{\"nodes\":[{\"id\":0,\"value\":{\"id\":\"A\"}},{\"id\":1,\"value\":{\"id\":\"B\"}},\
+             {\"id\":2,\"value\":{\"id\":\"C\"}},{\"id\":3,\"value\":{\"id\":\"D\"}}],\
+             \"edges\":[{\"from\":0,\"to\":1},{\"from\":0,\"to\":2,\"any_of_group\":0},\
+             {\"from\":1,\"to\":2,\"any_of_group\":0},{\"from\":2,\"to\":3}]}"
+        );
+
+        let copy: Dag<MockStruct> = serde_json::from_str(&json).unwrap();
+        assert!(copy == dag);
+        assert_eq!(copy.get_node(c).get_value().id, 'C');
+        assert!(copy.is_ready(c, |id| id == b));
+        assert_eq!(copy.get_dependants(c), &HashSet::from([d]));
+
+        let cycle =
+            "{\"nodes\":[{\"id\":0,\"value\":{\"id\":\"A\"}},{\"id\":1,\"value\":{\"id\":\"B\"}}],\
+                     \"edges\":[{\"from\":0,\"to\":1},{\"from\":1,\"to\":0}]}";
+        assert!(serde_json::from_str::<Dag<MockStruct>>(cycle).is_err());
+        let unknown =
+            "{\"nodes\":[{\"id\":0,\"value\":{\"id\":\"A\"}}],\"edges\":[{\"from\":0,\"to\":1}]}";
+        assert!(serde_json::from_str::<Dag<MockStruct>>(unknown).is_err());
+    }
+
+    #[cfg(feature = "petgraph")]
+    #[test]
+    fn petgraph_conversion() {
+        use petgraph::algo::{dominators, tarjan_scc, toposort};
+        use petgraph::graph::NodeIndex;
+
+        let mut dag = Dag::new();
+
+        let a = dag.add_node(MockStruct::new('A'));
+        let b = dag.add_node(MockStruct::new('B'));
+        let c = dag.add_node(MockStruct::new('C'));
+        let d = dag.add_node(MockStruct::new('D'));
+
+        dag.connect(a, b);
+        dag.connect(a, c);
+        dag.connect_any_of(b, d, 0);
+        dag.connect_any_of(c, d, 0);
+
+        let graph = dag.to_petgraph();
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 4);
+        assert_eq!(graph[NodeIndex::new(c)], MockStruct::new('C'));
+        let edge = graph
+            .find_edge(NodeIndex::new(c), NodeIndex::new(d))
+            .unwrap();
+        assert_eq!(graph[edge], Some(0));
+        assert!(graph
+            .find_edge(NodeIndex::new(b), NodeIndex::new(a))
+            .is_none());
+
+        let order = toposort(&graph, None).unwrap();
+        assert_eq!(order.first(), Some(&NodeIndex::new(a)));
+        assert_eq!(order.last(), Some(&NodeIndex::new(d)));
+        assert_eq!(tarjan_scc(&graph).len(), 4);
+        let dominators = dominators::simple_fast(&graph, NodeIndex::new(a));
+        assert_eq!(
+            dominators.immediate_dominator(NodeIndex::new(d)),
+            Some(NodeIndex::new(a))
+        );
+
+        let copy = Dag::from_petgraph(graph.clone()).unwrap();
+        assert!(copy == dag);
+
+        let mut cycle = graph;
+        cycle.add_edge(NodeIndex::new(d), NodeIndex::new(a), None);
+        assert!(Dag::from_petgraph(cycle).is_err());
+    }
+
     #[test]
     fn remove_nodes() {
         let mut dag = Dag::new();
@@ -11,6 +11,13 @@ enum CycleCheckStatus {
     Processed,
 }
 
+//
+// The visitation the scheduler used to start its tasks from, before it dispatched them from
+// dependency counters. Kept for code that still walks a `Dag` with it.
+//
+#[deprecated(
+    note = "use `Dag::topological_sort()` or `Dag::get_levels()` to visit a dag in dependency order"
+)]
 pub struct DagVisitationInfo<'a, T: Eq + Clone> {
     dag: &'a Dag<T>,
     dependencies: RefCell<Vec<HashSet<NodeId>>>, // upstream nodes
@@ -18,12 +25,13 @@ pub struct DagVisitationInfo<'a, T: Eq + Clone> {
     roots: RefCell<HashSet<NodeId>>,
 }
 
+#[allow(deprecated)]
 impl<'a, T: Eq + Clone> DagVisitationInfo<'a, T> {
     pub(crate) fn new(dag: &'a Dag<T>) -> Self {
         let len = dag.get_num_nodes();
 
         let mut result = Self {
-            dag: dag,
+            dag,
             dependencies: RefCell::new(Vec::with_capacity(len)),
             dependants: Vec::with_capacity(len),
             roots: RefCell::new(HashSet::new()),
@@ -103,18 +111,15 @@ impl<'a, T: Eq + Clone> DagVisitationInfo<'a, T> {
         self.roots.borrow_mut().remove(&node_id);
     }
 
-    fn get_roots(&self) -> Ref<HashSet<NodeId>> {
+    fn get_roots(&self) -> Ref<'_, HashSet<NodeId>> {
         self.roots.borrow()
     }
 
     fn get_next_root(&self) -> Option<NodeId> {
-        match self.roots.borrow().iter().next() {
-            Some(node_id) => Some(*node_id),
-            None => None,
-        }
+        self.roots.borrow().iter().next().copied()
     }
 
-    pub(crate) fn get_dependencies(&self, node_id: NodeId) -> Ref<HashSet<NodeId>> {
+    pub(crate) fn get_dependencies(&self, node_id: NodeId) -> Ref<'_, HashSet<NodeId>> {
         Ref::map(self.dependencies.borrow(), |vec| &vec[node_id])
     }
 
@@ -140,10 +145,7 @@ impl<'a, T: Eq + Clone> DagVisitationInfo<'a, T> {
         }
     }
 
-    pub fn next(&self) -> Option<RwLockReadGuard<Node<T>>> {
-        match self.get_next_root() {
-            Some(id) => Some(self.dag.get_node(id)),
-            None => None,
-        }
+    pub fn next(&self) -> Option<RwLockReadGuard<'_, Node<T>>> {
+        self.get_next_root().map(|id| self.dag.get_node(id))
     }
 }
@@ -0,0 +1,221 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+use crate::flow::Flow;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FlowChange {
+    TaskRemoved {
+        task: String,
+    },
+    TaskAdded {
+        task: String,
+    },
+    ConnectionRemoved {
+        source: String,
+        source_port: usize,
+        target: String,
+        target_port: usize,
+    },
+    ConnectionAdded {
+        source: String,
+        source_port: usize,
+        target: String,
+        target_port: usize,
+    },
+    OutputTypeChanged {
+        task: String,
+        port: usize,
+        old_type: String,
+        new_type: String,
+    },
+}
+
+impl fmt::Display for FlowChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlowChange::TaskRemoved { task } => write!(f, "- task {}", task),
+            FlowChange::TaskAdded { task } => write!(f, "+ task {}", task),
+            FlowChange::ConnectionRemoved {
+                source,
+                source_port,
+                target,
+                target_port,
+            } => write!(
+                f,
+                "- {}.out{} -> {}.in{}",
+                source, source_port, target, target_port
+            ),
+            FlowChange::ConnectionAdded {
+                source,
+                source_port,
+                target,
+                target_port,
+            } => write!(
+                f,
+                "+ {}.out{} -> {}.in{}",
+                source, source_port, target, target_port
+            ),
+            FlowChange::OutputTypeChanged {
+                task,
+                port,
+                old_type,
+                new_type,
+            } => write!(f, "~ {}.out{}: {} -> {}", task, port, old_type, new_type),
+        }
+    }
+}
+
+//
+// Structural differences between two flows, see `Flow::diff()`.
+//
+// Tasks are matched by name. Tasks sharing a name are matched in id order and told apart by a
+// `#2`, `#3`, ... suffix, and unnamed tasks are matched by id and shown as `task {id}`. Output
+// types are only compared for ports that are connected in both flows.
+//
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FlowDiff {
+    changes: Vec<FlowChange>, // removals first, then additions, then type changes
+}
+
+impl FlowDiff {
+    pub(crate) fn new(old_flow: &Flow, new_flow: &Flow) -> Self {
+        let old_labels = get_task_labels(old_flow);
+        let new_labels = get_task_labels(new_flow);
+        let old_tasks: BTreeSet<&String> = old_labels.iter().collect();
+        let new_tasks: BTreeSet<&String> = new_labels.iter().collect();
+        let old_connections = get_labeled_connections(old_flow, &old_labels);
+        let new_connections = get_labeled_connections(new_flow, &new_labels);
+
+        let mut changes = Vec::new();
+        for task in old_tasks.difference(&new_tasks) {
+            changes.push(FlowChange::TaskRemoved {
+                task: (*task).clone(),
+            });
+        }
+        for task in new_tasks.difference(&old_tasks) {
+            changes.push(FlowChange::TaskAdded {
+                task: (*task).clone(),
+            });
+        }
+        for (source, source_port, target, target_port) in
+            old_connections.difference(&new_connections)
+        {
+            changes.push(FlowChange::ConnectionRemoved {
+                source: source.clone(),
+                source_port: *source_port,
+                target: target.clone(),
+                target_port: *target_port,
+            });
+        }
+        for (source, source_port, target, target_port) in
+            new_connections.difference(&old_connections)
+        {
+            changes.push(FlowChange::ConnectionAdded {
+                source: source.clone(),
+                source_port: *source_port,
+                target: target.clone(),
+                target_port: *target_port,
+            });
+        }
+
+        let new_types = get_output_types(new_flow, &new_labels);
+        for ((task, port), old_type) in get_output_types(old_flow, &old_labels) {
+            if let Some(new_type) = new_types.get(&(task.clone(), port)) {
+                if old_type != *new_type {
+                    changes.push(FlowChange::OutputTypeChanged {
+                        task,
+                        port,
+                        old_type: String::from(old_type),
+                        new_type: String::from(*new_type),
+                    });
+                }
+            }
+        }
+
+        Self { changes }
+    }
+
+    pub fn get_changes(&self) -> &[FlowChange] {
+        &self.changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for FlowDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.changes.is_empty() {
+            return writeln!(f, "no changes");
+        }
+        for change in self.changes.iter() {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+// the label every task is matched by, indexed by task id
+fn get_task_labels(flow: &Flow) -> Vec<String> {
+    // every name is reserved for the first task carrying it, so that a generated label never
+    // takes the name of another task
+    let mut taken: HashSet<String> = (0..flow.get_num_tasks())
+        .map(|task_id| String::from(flow.get_task_name(task_id)))
+        .filter(|name| !name.is_empty())
+        .collect();
+    let mut num_seen = HashMap::<&str, usize>::new();
+    (0..flow.get_num_tasks())
+        .map(|task_id| {
+            let name = flow.get_task_name(task_id);
+            if name.is_empty() {
+                let mut label = format!("task {}", task_id);
+                let mut count = 1;
+                while taken.contains(&label) {
+                    count += 1;
+                    label = format!("task {} #{}", task_id, count);
+                }
+                taken.insert(label.clone());
+                return label;
+            }
+            let count = num_seen.entry(name).or_insert(0);
+            *count += 1;
+            if *count == 1 {
+                return String::from(name);
+            }
+            let mut label = format!("{} #{}", name, count);
+            while taken.contains(&label) {
+                *count += 1;
+                label = format!("{} #{}", name, count);
+            }
+            taken.insert(label.clone());
+            label
+        })
+        .collect()
+}
+
+fn get_labeled_connections(
+    flow: &Flow,
+    labels: &[String],
+) -> BTreeSet<(String, usize, String, usize)> {
+    flow.get_connections()
+        .map(|c| {
+            (
+                labels[c.source_task_id].clone(),
+                c.source_port,
+                labels[c.target_task_id].clone(),
+                c.target_port,
+            )
+        })
+        .collect()
+}
+
+fn get_output_types(flow: &Flow, labels: &[String]) -> BTreeMap<(String, usize), &'static str> {
+    flow.get_connections()
+        .filter_map(|c| {
+            let type_name = flow.get_output_type_name(c.source_task_id, c.source_port)?;
+            Some(((labels[c.source_task_id].clone(), c.source_port), type_name))
+        })
+        .collect()
+}
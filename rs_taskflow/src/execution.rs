@@ -1,145 +1,585 @@
-use std::future::Future;
-use std::pin::Pin;
-use std::sync::atomic::AtomicBool;
-use std::sync::atomic::Ordering::Relaxed;
-use std::sync::{Arc, Mutex, MutexGuard};
-use std::task::{Context, Poll, Waker};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io;
+use std::io::Write;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize};
+use std::sync::{Arc, Mutex};
 use std::thread;
+use std::thread::ThreadId;
+use std::time::Instant;
 
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::Notify;
 use tokio::task;
-use tokio::task::JoinHandle;
 
 use crate::dag::node::NodeId;
+use crate::dag::Dag;
 use crate::flow::{Flow, TaskHandle};
+use crate::observer::TaskInfo;
+#[cfg(feature = "serde")]
+use crate::replay::{RecordedOutput, Recording, ReplayError};
+use crate::report::ExecutionReport;
 use crate::task::*;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaskStatus {
+    Pending,
+    Running,
+    Completed,
+    // the task panicked or returned an error
+    Failed,
+    // the task did not run because an input it waits for failed or was skipped
+    Skipped,
+    // the task was left out of a replay, its recorded outputs were used instead
+    Replayed,
+    // the task was not needed for the targets of the execution, so its outputs are unavailable
+    Excluded,
+}
+
+impl TaskStatus {
+    const ALL: [TaskStatus; 7] = [
+        TaskStatus::Pending,
+        TaskStatus::Running,
+        TaskStatus::Completed,
+        TaskStatus::Failed,
+        TaskStatus::Skipped,
+        TaskStatus::Replayed,
+        TaskStatus::Excluded,
+    ];
+
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            TaskStatus::Completed
+                | TaskStatus::Failed
+                | TaskStatus::Skipped
+                | TaskStatus::Replayed
+                | TaskStatus::Excluded
+        )
+    }
+
+    pub(crate) fn get_dot_color(&self) -> &'static str {
+        match self {
+            TaskStatus::Pending => "white",
+            TaskStatus::Running => "gold",
+            TaskStatus::Completed => "palegreen",
+            TaskStatus::Failed => "tomato",
+            TaskStatus::Skipped => "lightgray",
+            TaskStatus::Replayed => "lightblue",
+            TaskStatus::Excluded => "whitesmoke",
+        }
+    }
+}
+
+//
+// Progress of an execution as it happens, see `Flow::execute_with_events()`. Every task that
+// runs sends `Ready`, `Started` and then either `Finished` or `Failed`; a task that cannot run
+// only sends `Skipped`. Replayed and excluded tasks send nothing.
+//
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExecutionEvent {
+    Ready(usize),
+    Started(usize),
+    Finished(usize),
+    Failed(usize, String), // task id and panic or error message
+    Skipped(usize),
+}
+
+// tracks one any-of group of dependencies of a task, see `Dag::connect_any_of()`
+struct AnyOfGroup {
+    group: usize,
+    size: usize,
+    satisfied: AtomicBool,
+    num_failed: AtomicUsize,
+    producer: Mutex<Option<NodeId>>, // the dependency that satisfied the group
+}
+
+enum Resolution {
+    Waiting,
+    Ready,
+    Blocked,
+}
+
 struct ExecTask {
-    waker: Mutex<Option<Waker>>,
-    completed: AtomicBool,
+    status: AtomicU8,
+    timing: Mutex<Option<(Instant, Instant, ThreadId)>>,
+    num_unresolved: AtomicUsize, // all-of dependencies and any-of groups that have not resolved
+    any_of_groups: Vec<AnyOfGroup>,
+    blocked: AtomicBool,
+    claimed: AtomicBool, // set by whoever gets to run or skip the task
 }
 
 impl ExecTask {
-    fn new() -> Self {
+    fn new(flow: &Flow, node_id: NodeId) -> Self {
+        let dag = flow.get_flow_graph();
+        let any_of_groups: Vec<AnyOfGroup> = dag
+            .get_any_of_dependencies(node_id)
+            .iter()
+            .map(|(group, members)| AnyOfGroup {
+                group: *group,
+                size: members.len(),
+                satisfied: AtomicBool::new(false),
+                num_failed: AtomicUsize::new(0),
+                producer: Mutex::new(None),
+            })
+            .collect();
         Self {
-            waker: Mutex::new(None),
-            completed: AtomicBool::new(false),
+            status: AtomicU8::new(TaskStatus::Pending as u8),
+            timing: Mutex::new(None),
+            num_unresolved: AtomicUsize::new(
+                dag.get_all_of_dependencies(node_id).len() + any_of_groups.len(),
+            ),
+            any_of_groups,
+            blocked: AtomicBool::new(false),
+            claimed: AtomicBool::new(false),
         }
     }
 
-    fn get_waker(&self) -> MutexGuard<Option<Waker>> {
-        self.waker.lock().unwrap()
+    fn get_timing(&self) -> Option<(Instant, Instant, ThreadId)> {
+        *self.timing.lock().unwrap()
+    }
+
+    fn set_timing(&self, start_time: Instant, end_time: Instant, worker: ThreadId) {
+        *self.timing.lock().unwrap() = Some((start_time, end_time, worker));
     }
 
-    fn is_completed(&self) -> bool {
-        self.completed.load(Relaxed)
+    fn get_status(&self) -> TaskStatus {
+        TaskStatus::ALL[self.status.load(SeqCst) as usize]
     }
 
-    fn set_completed(&self) {
-        self.completed.store(true, Relaxed)
+    fn set_status(&self, status: TaskStatus) {
+        self.status.store(status as u8, SeqCst)
+    }
+
+    fn claim(&self) -> bool {
+        !self.claimed.swap(true, SeqCst)
+    }
+
+    //
+    // Records that the dependency `dep_node_id` of the task `node_id` has finished. A dependency
+    // that fails blocks the task right away, any other outcome counts down the unresolved
+    // dependencies. Whoever blocks the task, or counts the last dependency of an unblocked task,
+    // claims the task and is told to skip or run it.
+    //
+    fn resolve_dependency(
+        &self,
+        dag: &Dag<Box<dyn ExecutableTask>>,
+        node_id: NodeId,
+        dep_node_id: NodeId,
+        succeeded: bool,
+    ) -> Resolution {
+        let mut num_resolved = 0;
+        let mut newly_blocked = false;
+        if dag.get_all_of_dependencies(node_id).contains(&dep_node_id) {
+            num_resolved += 1;
+            newly_blocked |= !succeeded;
+        }
+        for any_of_group in self.any_of_groups.iter() {
+            if !dag.get_any_of_dependencies(node_id)[&any_of_group.group].contains(&dep_node_id) {
+                continue;
+            }
+            if succeeded {
+                if !any_of_group.satisfied.swap(true, SeqCst) {
+                    *any_of_group.producer.lock().unwrap() = Some(dep_node_id);
+                    num_resolved += 1;
+                }
+            } else if any_of_group.num_failed.fetch_add(1, SeqCst) + 1 == any_of_group.size {
+                num_resolved += 1;
+                newly_blocked = true;
+            }
+        }
+
+        // blocked is set before counting down so that whoever counts down the last dependency
+        // sees it
+        if newly_blocked {
+            self.blocked.store(true, SeqCst);
+        }
+        let num_unresolved = self.num_unresolved.fetch_sub(num_resolved, SeqCst) - num_resolved;
+        if newly_blocked {
+            if self.claim() {
+                return Resolution::Blocked;
+            }
+        } else if num_resolved > 0
+            && num_unresolved == 0
+            && !self.blocked.load(SeqCst)
+            && self.claim()
+        {
+            return Resolution::Ready;
+        }
+        Resolution::Waiting
     }
 }
 
-struct ExecTaskFuture {
+// state shared by the workers running the tasks of one execution
+struct Scheduler {
     flow: Arc<Flow>,
-    node_id: NodeId,
     task_execs: Arc<Vec<ExecTask>>,
+    events: Option<UnboundedSender<ExecutionEvent>>,
+    num_finished: AtomicUsize,
+    all_finished: Notify,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
 }
 
-impl Future for ExecTaskFuture {
-    type Output = ();
-
-    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        if cfg!(debug_assertions) {
-            println!(
-                "{:?} Visiting node id {} (waker: {:?})",
-                thread::current().id(),
-                self.node_id,
-                cx.waker()
-            );
+impl Scheduler {
+    fn send_event(&self, event: ExecutionEvent) {
+        if let Some(events) = &self.events {
+            // the receiver may have been dropped, which only means nobody is listening
+            let _ = events.send(event);
         }
-        *self.task_execs[self.node_id].get_waker() = Some(cx.waker().clone());
+    }
 
-        for dep_node_id in self.flow.get_flow_graph().get_dependencies(self.node_id) {
-            if self.task_execs[*dep_node_id].is_completed() {
-                continue;
-            } else {
-                return Poll::Pending;
+    fn dispatch(self: &Arc<Self>, node_id: NodeId) {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(parent: &self.span, task_id = node_id, "dispatching");
+        let scheduler = self.clone();
+        task::spawn(async move { scheduler.run(node_id) });
+    }
+
+    // runs the task and then, for as long as it makes some dependants ready, one of them on the
+    // same worker while the others are dispatched
+    fn run(self: &Arc<Self>, mut node_id: NodeId) {
+        loop {
+            let succeeded = self.exec_task(node_id);
+            let mut ready = Vec::new();
+            self.finish_task(node_id, succeeded, &mut ready);
+            match ready.pop() {
+                Some(next_node_id) => {
+                    for ready_node_id in ready {
+                        self.dispatch(ready_node_id);
+                    }
+                    node_id = next_node_id;
+                }
+                None => return,
             }
         }
+    }
 
-        self.flow
-            .get_flow_graph()
-            .get_mut_node(self.node_id)
-            .get_mut_value()
-            .exec(self.flow.as_ref());
-
-        self.task_execs[self.node_id].set_completed();
-
-        for dep_node_id in self.flow.get_flow_graph().get_dependants(self.node_id) {
-            if let Some(waker) = self.task_execs[*dep_node_id].get_waker().take() {
-                if cfg!(debug_assertions) {
-                    println!(
-                        "{:?} Visited node id {} (waking: {:?})",
-                        thread::current().id(),
-                        self.node_id,
-                        waker
-                    );
+    // runs all tasks one after the other on the current worker, always the ready task with the
+    // lowest id first, so that every execution of the same flow runs its tasks in the same order
+    fn run_in_order(&self, ready: Vec<NodeId>) {
+        let mut ready: BinaryHeap<Reverse<NodeId>> = ready.into_iter().map(Reverse).collect();
+        let mut newly_ready = Vec::new();
+        while let Some(Reverse(node_id)) = ready.pop() {
+            let succeeded = self.exec_task(node_id);
+            self.finish_task(node_id, succeeded, &mut newly_ready);
+            ready.extend(newly_ready.drain(..).map(Reverse));
+        }
+    }
+
+    fn exec_task(&self, node_id: NodeId) -> bool {
+        let dag = self.flow.get_flow_graph();
+        #[cfg(feature = "tracing")]
+        let span = tracing::info_span!(
+            parent: &self.span,
+            "task",
+            task_id = node_id,
+            task_name = self.flow.get_task_name(node_id),
+            num_dependencies = dag.get_dependencies(node_id).len(),
+            num_dependants = dag.get_dependants(node_id).len(),
+        );
+        #[cfg(feature = "tracing")]
+        let _entered = span.enter();
+
+        let task_info = TaskInfo {
+            task_id: node_id,
+            task_name: self.flow.get_task_name(node_id),
+            worker: thread::current().id(),
+        };
+        self.task_execs[node_id].set_status(TaskStatus::Running);
+        self.send_event(ExecutionEvent::Started(node_id));
+        let start_time = Instant::now();
+        for observer in self.flow.get_observers() {
+            observer.on_entry(&task_info, start_time);
+        }
+        #[cfg(feature = "tracing")]
+        tracing::debug!(worker = ?task_info.worker, "executing");
+
+        // the node is locked outside of catch_unwind so that a panicking task does not poison it
+        let result = {
+            let mut node = dag.get_mut_node(node_id);
+            let task = node.get_mut_value();
+            for any_of_group in self.task_execs[node_id].any_of_groups.iter() {
+                if let Some(producer_id) = *any_of_group.producer.lock().unwrap() {
+                    task.set_input_producer(any_of_group.group, producer_id);
                 }
-                waker.wake();
+            }
+            panic::catch_unwind(AssertUnwindSafe(|| task.exec(self.flow.as_ref())))
+                .map_err(panic_message)
+                .and_then(|()| match task.get_error_message() {
+                    Some(message) => Err(message),
+                    None => Ok(()),
+                })
+        };
+
+        let end_time = Instant::now();
+        for observer in self.flow.get_observers() {
+            observer.on_exit(&task_info, start_time, end_time);
+        }
+        self.task_execs[node_id].set_timing(start_time, end_time, task_info.worker);
+
+        match result {
+            Ok(()) => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(elapsed = ?end_time.duration_since(start_time), "completed");
+                self.task_execs[node_id].set_status(TaskStatus::Completed);
+                self.send_event(ExecutionEvent::Finished(node_id));
+                true
+            }
+            Err(message) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(elapsed = ?end_time.duration_since(start_time), message, "failed");
+                self.task_execs[node_id].set_status(TaskStatus::Failed);
+                self.send_event(ExecutionEvent::Failed(node_id, message));
+                false
             }
         }
+    }
 
-        Poll::Ready(())
+    // lets the dependants of a finished task know, collecting those that became ready and
+    // skipping those that became blocked
+    fn finish_task(&self, node_id: NodeId, succeeded: bool, ready: &mut Vec<NodeId>) {
+        let dag = self.flow.get_flow_graph();
+        let mut finished = vec![(node_id, succeeded)];
+        while let Some((node_id, succeeded)) = finished.pop() {
+            let mut dependants: Vec<NodeId> = dag.get_dependants(node_id).iter().copied().collect();
+            if self.flow.is_deterministic() {
+                dependants.sort_unstable();
+            }
+            for dep_node_id in dependants {
+                let resolution = self.task_execs[dep_node_id].resolve_dependency(
+                    dag,
+                    dep_node_id,
+                    node_id,
+                    succeeded,
+                );
+                match resolution {
+                    Resolution::Waiting => {}
+                    Resolution::Ready => {
+                        #[cfg(feature = "tracing")]
+                        tracing::trace!(
+                            parent: &self.span,
+                            task_id = node_id,
+                            dependant_id = dep_node_id,
+                            "dependant ready"
+                        );
+                        self.send_event(ExecutionEvent::Ready(dep_node_id));
+                        ready.push(dep_node_id);
+                    }
+                    Resolution::Blocked => {
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!(parent: &self.span, task_id = dep_node_id, "skipped");
+                        self.task_execs[dep_node_id].set_status(TaskStatus::Skipped);
+                        self.send_event(ExecutionEvent::Skipped(dep_node_id));
+                        finished.push((dep_node_id, false));
+                    }
+                }
+            }
+
+            let num_finished = self.num_finished.fetch_add(1, SeqCst) + 1;
+            if num_finished == self.task_execs.len() {
+                self.all_finished.notify_one();
+            }
+        }
     }
 }
 
 pub struct Execution {
     flow: Arc<Flow>, // parent Flow object
+    task_execs: Arc<Vec<ExecTask>>,
+    timing: Option<(Instant, Instant)>,
+    events: Option<UnboundedSender<ExecutionEvent>>,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
 }
 
 impl Execution {
     pub(crate) fn new(flow: Arc<Flow>) -> Execution {
-        Execution { flow: flow }
+        Execution {
+            #[cfg(feature = "tracing")]
+            span: tracing::info_span!("execution", num_tasks = flow.get_num_tasks()),
+            task_execs: Arc::new(
+                (0..flow.get_num_tasks())
+                    .map(|node_id| ExecTask::new(flow.as_ref(), node_id))
+                    .collect(),
+            ),
+            timing: None,
+            events: None,
+            flow,
+        }
     }
 
-    fn spawn_exec_task(
-        &self,
-        node_id: NodeId,
-        task_execs_ref: &Arc<Vec<ExecTask>>,
-    ) -> JoinHandle<()> {
-        if cfg!(debug_assertions) {
-            println!("Spawning task for node id {}", node_id);
+    pub(crate) fn with_events(mut self, events: UnboundedSender<ExecutionEvent>) -> Execution {
+        self.events = Some(events);
+        self
+    }
+
+    // tasks left out of the run set are not run and get the status `status()` gives them, which
+    // is either `Replayed` or `Excluded`
+    pub(crate) fn with_run_set<F: Fn(usize) -> TaskStatus>(
+        self,
+        run_set: &[bool],
+        status: F,
+    ) -> Execution {
+        for (task_id, run) in run_set.iter().enumerate() {
+            if !run {
+                self.task_execs[task_id].set_status(status(task_id));
+                self.task_execs[task_id].claim();
+            }
         }
+        self
+    }
 
-        task::spawn(ExecTaskFuture {
-            flow: self.flow.clone(),
-            node_id,
-            task_execs: task_execs_ref.clone(),
-        })
+    // Collects the current values of the outputs registered with `Flow::record_output0()` and
+    // friends, for the tasks that completed.
+    #[cfg(feature = "serde")]
+    pub fn get_recording(&self) -> Result<Recording, ReplayError> {
+        let mut outputs = Vec::new();
+        for codec in self.flow.get_output_codecs() {
+            if self.get_task_status(codec.task_id) != TaskStatus::Completed {
+                continue;
+            }
+            let node = self.flow.get_flow_graph().get_node(codec.task_id);
+            let Some(value) = (codec.serialize)(node.get_value().as_ref()) else {
+                continue;
+            };
+            outputs.push(RecordedOutput {
+                task_id: codec.task_id,
+                task_name: String::from(self.flow.get_task_name(codec.task_id)),
+                port: codec.port,
+                value: value.map_err(|error| ReplayError::UnserializableOutput {
+                    task_id: codec.task_id,
+                    port: codec.port,
+                    error,
+                })?,
+            });
+        }
+        Ok(Recording::new(outputs))
     }
 
-    pub async fn start_and_finish(self) -> Self {
+    pub fn get_task_status(&self, task_id: usize) -> TaskStatus {
+        self.task_execs[task_id].get_status()
+    }
+
+    pub fn get_task_error<T: FallibleTask>(
+        &self,
+        task_handle: &TaskHandle<T>,
+    ) -> Option<&T::Error> {
+        let read_handle = self.flow.get_task(task_handle);
+        let error_ref = T::get_error(read_handle.borrow());
+        let error_ptr: *const T::Error = error_ref?;
+        unsafe { Some(&*error_ptr) }
+    }
+
+    //
+    // Summarises where the time of a finished execution went: the wall-clock time of every task,
+    // how busy each worker thread was and the critical path through the flow, i.e. the chain of
+    // dependent tasks with the longest total duration.
+    //
+    pub fn get_report(&self) -> ExecutionReport {
+        let (start_time, end_time) = self.timing.expect("execution has not finished");
+        let task_timings: Vec<_> = self.task_execs.iter().map(|t| t.get_timing()).collect();
+        ExecutionReport::new(self.flow.as_ref(), start_time, end_time, &task_timings)
+    }
+
+    // Renders the flow like `Flow::to_dot()`, with every task coloured by its status in this
+    // execution.
+    pub fn to_dot(&self) -> String {
+        let mut dot = Vec::new();
+        self.write_dot(&mut dot).unwrap();
+        String::from_utf8(dot).unwrap()
+    }
+
+    pub fn write_dot<W: Write>(&self, writer: W) -> io::Result<()> {
+        self.flow
+            .write_dot_with_status(writer, |task_id| Some(self.get_task_status(task_id)))
+    }
+
+    pub async fn start_and_finish(mut self) -> Self {
         let len = self.flow.get_num_tasks();
-        let mut task_execs_vec = Vec::<ExecTask>::with_capacity(len);
-        for _ in 0..len {
-            task_execs_vec.push(ExecTask::new());
+        let start_time = Instant::now();
+        for observer in self.flow.get_observers() {
+            observer.on_execution_start(len, start_time);
         }
-        let task_execs = Arc::new(task_execs_vec);
+        #[cfg(feature = "tracing")]
+        tracing::debug!(parent: &self.span, "execution started");
+
+        let scheduler = Arc::new(Scheduler {
+            flow: self.flow.clone(),
+            task_execs: self.task_execs.clone(),
+            events: self.events.take(),
+            num_finished: AtomicUsize::new(0),
+            all_finished: Notify::new(),
+            #[cfg(feature = "tracing")]
+            span: self.span.clone(),
+        });
 
-        let mut join_handles = Vec::<JoinHandle<()>>::with_capacity(len);
-        let bfs = self.flow.get_flow_graph().build_bfs().unwrap();
-        while let Some(node) = bfs.next() {
-            bfs.visited_node(&*node);
-            let join_handle = self.spawn_exec_task(node.get_id(), &task_execs);
-            join_handles.push(join_handle);
+        // tasks left out of a replay count as finished from the start, while excluded tasks only
+        // have excluded dependants
+        let mut ready = Vec::new();
+        for node_id in 0..len {
+            match self.task_execs[node_id].get_status() {
+                TaskStatus::Replayed => scheduler.finish_task(node_id, true, &mut ready),
+                TaskStatus::Excluded => {
+                    scheduler.num_finished.fetch_add(1, SeqCst);
+                }
+                _ => {}
+            }
+        }
+
+        // a task on a dependency cycle can never become ready, so it fails right away and the
+        // tasks waiting on it are skipped
+        let dag = self.flow.get_flow_graph();
+        if dag.get_levels().is_err() {
+            let cycle_node_ids: Vec<NodeId> = (0..len)
+                .filter(|node_id| dag.is_reachable(*node_id, *node_id))
+                .filter(|node_id| self.task_execs[*node_id].claim())
+                .collect();
+            for node_id in cycle_node_ids.iter() {
+                self.task_execs[*node_id].set_status(TaskStatus::Failed);
+                scheduler.send_event(ExecutionEvent::Failed(
+                    *node_id,
+                    String::from("task is part of a dependency cycle"),
+                ));
+            }
+            for node_id in cycle_node_ids {
+                scheduler.finish_task(node_id, false, &mut ready);
+            }
         }
 
-        for (node_id, join_handle) in join_handles.into_iter().enumerate() {
-            if !task_execs[node_id].is_completed() {
-                let result = join_handle.await;
-                assert!(result.is_ok());
+        for node_id in 0..len {
+            let task_exec = &self.task_execs[node_id];
+            if task_exec.num_unresolved.load(SeqCst) == 0 && task_exec.claim() {
+                scheduler.send_event(ExecutionEvent::Ready(node_id));
+                ready.push(node_id);
             }
         }
+        if self.flow.is_single_worker() {
+            let worker = scheduler.clone();
+            task::spawn(async move { worker.run_in_order(ready) })
+                .await
+                .unwrap();
+        } else {
+            for node_id in ready {
+                scheduler.dispatch(node_id);
+            }
+        }
+
+        if scheduler.num_finished.load(SeqCst) < len {
+            scheduler.all_finished.notified().await;
+        }
+        drop(scheduler); // ends the event stream once the workers have dropped their references
+
+        let end_time = Instant::now();
+        self.timing = Some((start_time, end_time));
+        for observer in self.flow.get_observers() {
+            observer.on_execution_end(end_time);
+        }
+        #[cfg(feature = "tracing")]
+        tracing::debug!(parent: &self.span, "execution finished");
 
         self
     }
@@ -153,7 +593,7 @@ impl Execution {
     ) -> Option<&O> {
         let read_handle = self.flow.get_task(task_handle);
         let val_ref = T::get_output_0(read_handle.borrow());
-        let val_ptr: *const O = val_ref.unwrap();
+        let val_ptr: *const O = val_ref?;
         unsafe { Some(&*val_ptr) }
     }
     #[cfg(not(feature = "macro_task_ifaces"))]
@@ -163,7 +603,7 @@ impl Execution {
     ) -> Option<&O> {
         let read_handle = self.flow.get_task(task_handle);
         let val_ref = T::get_output_1(read_handle.borrow());
-        let val_ptr: *const O = val_ref.unwrap();
+        let val_ptr: *const O = val_ref?;
         unsafe { Some(&*val_ptr) }
     }
 }
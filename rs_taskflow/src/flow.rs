@@ -1,11 +1,23 @@
+use std::any::Any;
+use std::collections::HashMap;
 use std::future::Future;
+use std::io;
+use std::io::Write;
 use std::marker::PhantomData;
 
 use crate::dag::node::Node;
 use crate::dag::Dag;
-use crate::execution::Execution;
+use crate::diff::FlowDiff;
+use crate::execution::{Execution, ExecutionEvent, TaskStatus};
+use crate::observer::Observer;
+#[cfg(feature = "serde")]
+use crate::registry::{FlowDescription, RegistryError, TaskDescription, TaskRegistry};
+#[cfg(feature = "serde")]
+use crate::replay::{OutputCodec, Recording, ReplayError};
 use crate::task::*;
 use std::sync::{Arc, RwLockReadGuard, RwLockWriteGuard};
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::UnboundedReceiver;
 
 type NodeDataBaseType = Box<dyn ExecutableTask>;
 
@@ -15,11 +27,29 @@ pub struct TaskHandle<T> {
 }
 
 impl<T> TaskHandle<T> {
+    pub(crate) fn from_id(task_id: usize) -> Self {
+        Self {
+            task_id,
+            data_type: PhantomData,
+        }
+    }
+
     pub fn id(&self) -> usize {
         self.task_id
     }
 }
 
+// named ports of tasks that declare them, see `TaskPorts`
+impl<T: TaskPorts> TaskHandle<T> {
+    pub fn inputs(&self) -> T::Inputs {
+        T::get_inputs(self.task_id)
+    }
+
+    pub fn outputs(&self) -> T::Outputs {
+        T::get_outputs(self.task_id)
+    }
+}
+
 pub(crate) struct TaskReadHandle<'a, T> {
     guard: RwLockReadGuard<'a, Node<NodeDataBaseType>>,
     data_type: PhantomData<T>,
@@ -54,58 +84,438 @@ impl<'a, T: 'static> TaskWriteHandle<'a, T> {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Connection {
+    pub source_task_id: usize,
+    pub source_port: usize,
+    pub target_task_id: usize,
+    pub target_port: usize,
+}
+
+// a task id passed to the flow that is not the id of one of its tasks
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnknownTaskError {
+    pub task_id: usize,
+}
+
+impl std::fmt::Display for UnknownTaskError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "task {} does not exist", self.task_id)
+    }
+}
+
+impl std::error::Error for UnknownTaskError {}
+
 #[derive(Clone)]
 pub struct Flow {
     dag: Dag<NodeDataBaseType>,
+    task_names: Vec<String>,
+    input_connections: Vec<Vec<Connection>>, // indexed by target task id
+    observers: Vec<Arc<dyn Observer>>,
+    deterministic: bool,
+    single_worker: bool,
+    cluster_labels: Vec<String>,
+    task_clusters: Vec<Option<usize>>, // indexed by task id
+    injected_outputs: HashMap<(usize, usize), Arc<dyn Any + Send + Sync>>, // by task id and port
+    output_types: HashMap<(usize, usize), &'static str>, // of connected outputs, by task id and port
+    #[cfg(feature = "serde")]
+    output_codecs: Vec<OutputCodec>,
+}
+
+fn escape_dot_string(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
 }
 
 impl Flow {
     pub fn new() -> Self {
-        Self { dag: Dag::new() }
+        Self {
+            dag: Dag::new(),
+            task_names: Vec::new(),
+            input_connections: Vec::new(),
+            observers: Vec::new(),
+            deterministic: false,
+            single_worker: false,
+            cluster_labels: Vec::new(),
+            task_clusters: Vec::new(),
+            injected_outputs: HashMap::new(),
+            output_types: HashMap::new(),
+            #[cfg(feature = "serde")]
+            output_codecs: Vec::new(),
+        }
     }
 
     pub fn add_new_task<O, T: TaskOutput0<O>>(&mut self, new_task: T) -> TaskHandle<T> {
-        let id = self.dag.add_node(Box::new(new_task));
         TaskHandle {
-            task_id: id,
+            task_id: self.add_boxed_task(Box::new(new_task)),
             data_type: PhantomData,
         }
     }
 
+    //
+    // A handle to an existing task, e.g. of a flow rebuilt with `FlowDescription::to_flow()`.
+    // Returns None if there is no such task or it is not a `T`.
+    //
+    pub fn get_task_handle<T: ExecutableTask>(&self, task_id: usize) -> Option<TaskHandle<T>> {
+        if task_id >= self.get_num_tasks() {
+            return None;
+        }
+        let is_task_type = self
+            .dag
+            .get_node(task_id)
+            .get_value()
+            .as_ref()
+            .as_any()
+            .is::<T>();
+        is_task_type.then(|| TaskHandle::from_id(task_id))
+    }
+
+    pub(crate) fn add_boxed_task(&mut self, new_task: NodeDataBaseType) -> usize {
+        let id = self.dag.add_node(new_task);
+        self.task_names.push(String::new());
+        self.input_connections.push(Vec::new());
+        self.task_clusters.push(None);
+        id
+    }
+
     pub fn get_num_tasks(&self) -> usize {
         self.dag.get_num_nodes()
     }
 
-    pub(crate) fn get_task_by_id<T>(&self, task_id: usize) -> TaskReadHandle<T> {
+    pub fn set_task_name<T>(&mut self, task_handle: &TaskHandle<T>, name: &str) {
+        self.task_names[task_handle.id()] = String::from(name);
+    }
+
+    pub fn get_task_name(&self, task_id: usize) -> &str {
+        &self.task_names[task_id]
+    }
+
+    //
+    // Clusters group tasks that belong together, e.g. the tasks making up an algorithm subgraph,
+    // and are only used to lay out the graph when it is exported.
+    //
+    pub fn add_cluster(&mut self, label: &str) -> usize {
+        self.cluster_labels.push(String::from(label));
+        self.cluster_labels.len() - 1
+    }
+
+    pub fn set_task_cluster<T>(&mut self, task_handle: &TaskHandle<T>, cluster: usize) {
+        assert!(
+            cluster < self.cluster_labels.len(),
+            "unknown cluster {}",
+            cluster
+        );
+        self.task_clusters[task_handle.id()] = Some(cluster);
+    }
+
+    pub fn get_task_cluster(&self, task_id: usize) -> Option<usize> {
+        self.task_clusters[task_id]
+    }
+
+    pub fn get_cluster_label(&self, cluster: usize) -> &str {
+        &self.cluster_labels[cluster]
+    }
+
+    //
+    // In deterministic mode an execution handles ready tasks in ascending id order: the roots are
+    // dispatched lowest id first and the dependants of a finished task are resolved lowest id
+    // first. The dag keeps its adjacency in hash sets, whose iteration order is not stable, so the
+    // order comes only from this sorting by id. With several workers the tasks still run in
+    // parallel and may start in a different order; combined with `set_single_worker(true)` the
+    // tasks start in the same order and the same events are sent every time the flow is executed.
+    //
+    pub fn set_deterministic(&mut self, deterministic: bool) {
+        self.deterministic = deterministic;
+    }
+
+    pub fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    //
+    // With a single worker an execution runs its tasks one at a time, always picking the ready
+    // task with the lowest id, no matter how many worker threads the runtime has. It is meant for
+    // reproducing runs rather than for speed.
+    //
+    pub fn set_single_worker(&mut self, single_worker: bool) {
+        self.single_worker = single_worker;
+    }
+
+    pub fn is_single_worker(&self) -> bool {
+        self.single_worker
+    }
+
+    pub fn add_observer(&mut self, observer: Arc<dyn Observer>) {
+        self.observers.push(observer);
+    }
+
+    pub(crate) fn get_observers(&self) -> &[Arc<dyn Observer>] {
+        &self.observers
+    }
+
+    // an output value that replaces the one the task would compute, see `replay()`
+    pub(crate) fn get_injected_output(
+        &self,
+        task_id: usize,
+        port: usize,
+    ) -> Option<&(dyn Any + Send + Sync)> {
+        if self.injected_outputs.is_empty() {
+            return None;
+        }
+        self.injected_outputs
+            .get(&(task_id, port))
+            .map(|value| value.as_ref())
+    }
+
+    pub(crate) fn get_task_by_id<T>(&self, task_id: usize) -> TaskReadHandle<'_, T> {
         TaskReadHandle {
             guard: self.dag.get_node(task_id),
             data_type: PhantomData,
         }
     }
 
-    pub(crate) fn get_task<T>(&self, task_handle: &TaskHandle<T>) -> TaskReadHandle<T> {
+    pub(crate) fn get_task<T>(&self, task_handle: &TaskHandle<T>) -> TaskReadHandle<'_, T> {
         self.get_task_by_id(task_handle.id())
     }
 
-    pub(crate) fn get_mut_task<T>(&self, task_handle: &TaskHandle<T>) -> TaskWriteHandle<T> {
+    pub(crate) fn get_mut_task<T>(&self, task_handle: &TaskHandle<T>) -> TaskWriteHandle<'_, T> {
         TaskWriteHandle {
             guard: self.dag.get_mut_node(task_handle.id()),
             data_type: PhantomData,
         }
     }
 
-    fn connect<I, O, A: TaskOutput0<O>, B: TaskInput0<I>, T: 'static>(
+    //
+    // Describes the structure of the flow, with the tasks saved by `registry`, in a form that can
+    // be serialized, stored and compared, see `FlowDescription`.
+    //
+    #[cfg(feature = "serde")]
+    pub fn to_description(
+        &self,
+        registry: &TaskRegistry,
+    ) -> Result<FlowDescription, RegistryError> {
+        let graph = self.dag.try_map(|task_id, task| {
+            let (kind, payload) = registry.save_task(task_id, task.as_ref())?;
+            Ok(TaskDescription {
+                name: String::from(self.get_task_name(task_id)),
+                kind,
+                cluster: self.get_task_cluster(task_id),
+                payload,
+            })
+        })?;
+
+        Ok(FlowDescription {
+            graph,
+            clusters: self.cluster_labels.clone(),
+            connections: self.get_connections().copied().collect(),
+        })
+    }
+
+    //
+    // The tasks as a petgraph graph, with task `i` at node index `i` weighted by its name and one
+    // edge per connection, so a task feeding several inputs of another has several edges to it.
+    //
+    #[cfg(feature = "petgraph")]
+    pub fn to_petgraph(&self) -> petgraph::Graph<String, Connection> {
+        let mut graph = petgraph::Graph::with_capacity(self.get_num_tasks(), 0);
+        for task_id in 0..self.get_num_tasks() {
+            graph.add_node(String::from(self.get_task_name(task_id)));
+        }
+        for connection in self.get_connections() {
+            graph.add_edge(
+                petgraph::graph::NodeIndex::new(connection.source_task_id),
+                petgraph::graph::NodeIndex::new(connection.target_task_id),
+                *connection,
+            );
+        }
+        graph
+    }
+
+    pub fn get_connections(&self) -> impl Iterator<Item = &Connection> {
+        self.input_connections.iter().flatten()
+    }
+
+    pub fn get_input_connections(&self, task_id: usize) -> &[Connection] {
+        &self.input_connections[task_id]
+    }
+
+    // name of the type of an output port, known once the port has been connected
+    pub fn get_output_type_name(&self, task_id: usize, port: usize) -> Option<&'static str> {
+        self.output_types.get(&(task_id, port)).copied()
+    }
+
+    // how `other` differs from this flow, with tasks matched by name
+    pub fn diff(&self, other: &Flow) -> FlowDiff {
+        FlowDiff::new(self, other)
+    }
+
+    //
+    // Connects two ports picked by name, e.g.
+    // `flow.connect_ports(task_c.outputs().parity, task_f.inputs().flag)`, which is the same as
+    // connecting them with the `connect_outputX_to_inputY()` function of their positions.
+    //
+    pub fn connect_ports<B: ExecutableTask, T: 'static>(
+        &mut self,
+        output: OutputPort<T>,
+        input: InputPort<B, T>,
+    ) {
+        self.connect(
+            &TaskHandle::<()>::from_id(output.get_task_id()),
+            output.get_port(),
+            output.get_output_func(),
+            &TaskHandle::<B>::from_id(input.get_task_id()),
+            input.get_port(),
+            input.get_input_func(),
+        );
+    }
+
+    fn connect<A, B: ExecutableTask, T: 'static>(
         &mut self,
         task1_handle: &TaskHandle<A>,
+        task1_port: usize,
         task1_output: fn(&dyn ExecutableTask) -> Option<&T>,
         task2_handle: &TaskHandle<B>,
+        task2_port: usize,
         task2_input: fn(&mut B, TaskInputHandle<T>),
     ) {
+        let connection = Connection {
+            source_task_id: task1_handle.id(),
+            source_port: task1_port,
+            target_task_id: task2_handle.id(),
+            target_port: task2_port,
+        };
+        if self.is_connected(&connection) {
+            return;
+        }
+        if let Some(error) = self.get_connection_error(&connection) {
+            panic!("{}", error);
+        }
+
         (task2_input)(
             self.get_mut_task(task2_handle).borrow_concrete(),
-            TaskInputHandle::new(task1_handle.id(), task1_output),
+            TaskInputHandle::new(task1_handle.id(), task1_output).with_source_port(task1_port),
         );
-        self.dag.connect(task1_handle.id(), task2_handle.id());
+        self.add_connection(connection, std::any::type_name::<T>());
+    }
+
+    //
+    // Like `connect()`, for ports whose types are only known at run time. Returns false, without
+    // connecting anything, if the input does not take the output. The connections of a flow never
+    // hold two producers of an input that replaces them, so a second one is not taken either.
+    //
+    #[cfg(feature = "serde")]
+    pub(crate) fn connect_erased(
+        &mut self,
+        connection: Connection,
+        output: &ErasedOutput,
+        set_input: fn(&mut dyn ExecutableTask, usize, &ErasedOutput) -> bool,
+    ) -> bool {
+        if self.is_connected(&connection) {
+            return true;
+        }
+        let replaces = self.get_join_policy(connection.target_task_id, connection.target_port)
+            == JoinPolicy::Replace
+            && self.input_connections[connection.target_task_id]
+                .iter()
+                .any(|c| c.target_port == connection.target_port);
+        if replaces || self.get_connection_error(&connection).is_some() {
+            return false;
+        }
+        let connected = {
+            let mut node = self.dag.get_mut_node(connection.target_task_id);
+            set_input(
+                node.get_mut_value().as_mut(),
+                connection.target_port,
+                output,
+            )
+        };
+        if connected {
+            self.add_connection(connection, output.get_type_name());
+        }
+        connected
+    }
+
+    //
+    // Connecting the same output to the same input again leaves the flow as it is, so that every
+    // task reads the producer once and the recorded connections match what the tasks read.
+    //
+    fn is_connected(&self, connection: &Connection) -> bool {
+        self.input_connections[connection.target_task_id].contains(connection)
+    }
+
+    fn get_join_policy(&self, task_id: usize, port: usize) -> JoinPolicy {
+        self.dag
+            .get_node(task_id)
+            .get_value()
+            .get_input_join_policy(port)
+    }
+
+    //
+    // Why the input does not take the connection, if it does not. A unique input only takes one
+    // producer, and a first_completed input cannot tell apart two outputs of the same task since
+    // the scheduler only knows which task completed.
+    //
+    fn get_connection_error(&self, connection: &Connection) -> Option<String> {
+        let mut producers = self.input_connections[connection.target_task_id]
+            .iter()
+            .filter(|c| c.target_port == connection.target_port);
+        match self.get_join_policy(connection.target_task_id, connection.target_port) {
+            JoinPolicy::Unique if producers.next().is_some() => Some(format!(
+                "input {} of task {} already has a producer and does not accept another",
+                connection.target_port, connection.target_task_id
+            )),
+            JoinPolicy::FirstCompleted
+                if producers.any(|c| c.source_task_id == connection.source_task_id) =>
+            {
+                Some(format!(
+                    "input {} of task {} already has a producer in task {}, which a first_completed input does not accept twice",
+                    connection.target_port, connection.target_task_id, connection.source_task_id
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    // records a connection whose input has been set
+    fn add_connection(&mut self, connection: Connection, output_type: &'static str) {
+        let join_policy = self.get_join_policy(connection.target_task_id, connection.target_port);
+        if join_policy == JoinPolicy::Replace {
+            self.remove_replaced_connection(&connection);
+        }
+        self.input_connections[connection.target_task_id].push(connection);
+        self.output_types.insert(
+            (connection.source_task_id, connection.source_port),
+            output_type,
+        );
+        match join_policy {
+            JoinPolicy::Replace | JoinPolicy::Unique | JoinPolicy::Collect => self
+                .dag
+                .connect(connection.source_task_id, connection.target_task_id),
+            JoinPolicy::FirstCompleted => self.dag.connect_any_of(
+                connection.source_task_id,
+                connection.target_task_id,
+                connection.target_port,
+            ),
+        }
+    }
+
+    // the producer a new connection replaces stays a dependency only if it is waited for elsewhere
+    fn remove_replaced_connection(&mut self, connection: &Connection) {
+        let task_id = connection.target_task_id;
+        let Some(index) = self.input_connections[task_id]
+            .iter()
+            .position(|c| c.target_port == connection.target_port)
+        else {
+            return;
+        };
+        let replaced = self.input_connections[task_id].remove(index);
+        let still_waited_for = self.input_connections[task_id].iter().any(|c| {
+            c.source_task_id == replaced.source_task_id
+                && self.get_join_policy(task_id, c.target_port) != JoinPolicy::FirstCompleted
+        });
+        if !still_waited_for {
+            self.dag.disconnect(replaced.source_task_id, task_id);
+        }
     }
 
     #[cfg(feature = "macro_task_ifaces")]
@@ -116,7 +526,14 @@ impl Flow {
         task1_handle: &TaskHandle<A>,
         task2_handle: &TaskHandle<B>,
     ) {
-        self.connect(task1_handle, A::get_output_0, task2_handle, B::set_input_0);
+        self.connect(
+            task1_handle,
+            0,
+            A::get_output_0,
+            task2_handle,
+            0,
+            B::set_input_0,
+        );
     }
     #[cfg(not(feature = "macro_task_ifaces"))]
     pub fn connect_output0_to_input1<I0, T: 'static, A: TaskOutput0<T>, B: TaskInput1<I0, T>>(
@@ -124,7 +541,14 @@ impl Flow {
         task1_handle: &TaskHandle<A>,
         task2_handle: &TaskHandle<B>,
     ) {
-        self.connect(task1_handle, A::get_output_0, task2_handle, B::set_input_1);
+        self.connect(
+            task1_handle,
+            0,
+            A::get_output_0,
+            task2_handle,
+            1,
+            B::set_input_1,
+        );
     }
     #[cfg(not(feature = "macro_task_ifaces"))]
     pub fn connect_output1_to_input0<O0, T: 'static, A: TaskOutput1<O0, T>, B: TaskInput0<T>>(
@@ -132,7 +556,14 @@ impl Flow {
         task1_handle: &TaskHandle<A>,
         task2_handle: &TaskHandle<B>,
     ) {
-        self.connect(task1_handle, A::get_output_1, task2_handle, B::set_input_0);
+        self.connect(
+            task1_handle,
+            1,
+            A::get_output_1,
+            task2_handle,
+            0,
+            B::set_input_0,
+        );
     }
     #[cfg(not(feature = "macro_task_ifaces"))]
     pub fn connect_output1_to_input1<
@@ -146,18 +577,251 @@ impl Flow {
         task1_handle: &TaskHandle<A>,
         task2_handle: &TaskHandle<B>,
     ) {
-        self.connect(task1_handle, A::get_output_1, task2_handle, B::set_input_1);
+        self.connect(
+            task1_handle,
+            1,
+            A::get_output_1,
+            task2_handle,
+            1,
+            B::set_input_1,
+        );
+    }
+
+    //
+    // Registers an output port whose value `Execution::get_recording()` captures. Outputs are only
+    // recorded once registered: whether a port type is serializable is only known where the port
+    // is named with its concrete type, so the flow cannot find every serializable output by itself.
+    //
+    #[cfg(feature = "serde")]
+    pub fn record_output<
+        T: 'static + serde::Serialize + serde::de::DeserializeOwned + Send + Sync,
+    >(
+        &mut self,
+        task_id: usize,
+        port: usize,
+        get_output: fn(&dyn ExecutableTask) -> Option<&T>,
+    ) {
+        self.output_codecs
+            .retain(|codec| codec.task_id != task_id || codec.port != port);
+        self.output_codecs
+            .push(OutputCodec::new(task_id, port, get_output));
+    }
+
+    #[cfg(all(feature = "serde", feature = "macro_task_ifaces"))]
+    rs_taskflow_derive::generate_record_output_funcs!(10);
+    #[cfg(all(feature = "serde", not(feature = "macro_task_ifaces")))]
+    pub fn record_output0<
+        O: 'static + serde::Serialize + serde::de::DeserializeOwned + Send + Sync,
+        T: TaskOutput0<O>,
+    >(
+        &mut self,
+        task_handle: &TaskHandle<T>,
+    ) {
+        self.record_output(task_handle.id(), 0, T::get_output_0);
+    }
+    #[cfg(all(feature = "serde", not(feature = "macro_task_ifaces")))]
+    pub fn record_output1<
+        O0,
+        O: 'static + serde::Serialize + serde::de::DeserializeOwned + Send + Sync,
+        T: TaskOutput1<O0, O>,
+    >(
+        &mut self,
+        task_handle: &TaskHandle<T>,
+    ) {
+        self.record_output(task_handle.id(), 1, T::get_output_1);
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn get_output_codecs(&self) -> &[OutputCodec] {
+        &self.output_codecs
     }
 
     pub(crate) fn get_flow_graph(&self) -> &Dag<NodeDataBaseType> {
         &self.dag
     }
 
+    // Groups the task ids into levels as `Dag::get_levels()` does: tasks in the same level do not
+    // depend on each other and can run in parallel.
+    pub fn get_levels(&self) -> Result<Vec<Vec<usize>>, &'static str> {
+        self.dag.get_levels()
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = Vec::new();
+        self.write_dot(&mut dot).unwrap();
+        String::from_utf8(dot).unwrap()
+    }
+
+    pub fn write_dot<W: Write>(&self, writer: W) -> io::Result<()> {
+        self.write_dot_with_status(writer, |_| None)
+    }
+
+    pub(crate) fn write_dot_with_status<W: Write, F: Fn(usize) -> Option<TaskStatus>>(
+        &self,
+        mut writer: W,
+        get_status: F,
+    ) -> io::Result<()> {
+        let write_node = |writer: &mut W, task_id: usize, indent: &str| -> io::Result<()> {
+            let name = self.get_task_name(task_id);
+            let label = if name.is_empty() {
+                format!("task {}", task_id)
+            } else {
+                format!("{} ({})", escape_dot_string(name), task_id)
+            };
+            write!(writer, "{}task{} [label=\"{}\"", indent, task_id, label)?;
+            if let Some(status) = get_status(task_id) {
+                write!(
+                    writer,
+                    ", style=filled, fillcolor=\"{}\", tooltip=\"{:?}\"",
+                    status.get_dot_color(),
+                    status
+                )?;
+            }
+            writeln!(writer, "];")
+        };
+
+        writeln!(writer, "digraph Flow {{")?;
+        for (cluster, label) in self.cluster_labels.iter().enumerate() {
+            writeln!(writer, "  subgraph cluster_{} {{", cluster)?;
+            writeln!(writer, "    label=\"{}\";", escape_dot_string(label))?;
+            for task_id in 0..self.get_num_tasks() {
+                if self.task_clusters[task_id] == Some(cluster) {
+                    write_node(&mut writer, task_id, "    ")?;
+                }
+            }
+            writeln!(writer, "  }}")?;
+        }
+        for task_id in 0..self.get_num_tasks() {
+            if self.task_clusters[task_id].is_none() {
+                write_node(&mut writer, task_id, "  ")?;
+            }
+        }
+        for connection in self.get_connections() {
+            writeln!(
+                writer,
+                "  task{} -> task{} [label=\"out{} -> in{}\"];",
+                connection.source_task_id,
+                connection.target_task_id,
+                connection.source_port,
+                connection.target_port
+            )?;
+        }
+        writeln!(writer, "}}")
+    }
+
     pub fn execute(&self) -> impl Future<Output = Execution> {
         let flow_copy = Arc::new(self.clone());
         let flow_exec = Execution::new(flow_copy);
         flow_exec.start_and_finish()
     }
+
+    //
+    // Runs only the tasks in `task_ids` and the tasks they transitively depend on. All other tasks
+    // are left `Excluded` in the returned execution and their outputs are unavailable.
+    //
+    pub fn execute_targets(
+        &self,
+        task_ids: &[usize],
+    ) -> Result<impl Future<Output = Execution>, UnknownTaskError> {
+        let mut run_set = vec![false; self.get_num_tasks()];
+        for task_id in task_ids {
+            if *task_id >= run_set.len() {
+                return Err(UnknownTaskError { task_id: *task_id });
+            }
+            run_set[*task_id] = true;
+            for dep_task_id in self.dag.ancestors(*task_id) {
+                run_set[dep_task_id] = true;
+            }
+        }
+
+        let flow_copy = Arc::new(self.clone());
+        let flow_exec = Execution::new(flow_copy).with_run_set(&run_set, |_| TaskStatus::Excluded);
+        Ok(flow_exec.start_and_finish())
+    }
+
+    //
+    // Runs only the tasks in `task_ids`. Whenever one of them reads an output of a task that is
+    // not run, the value is taken from `recording` instead, so a task deep inside the flow can be
+    // rerun in isolation with the inputs it saw when the recording was made. The tasks they depend
+    // on are reported as `Replayed`, and every other task as `Excluded`.
+    //
+    #[cfg(feature = "serde")]
+    pub fn replay(
+        &self,
+        recording: &Recording,
+        task_ids: &[usize],
+    ) -> Result<impl Future<Output = Execution>, ReplayError> {
+        let mut run_set = vec![false; self.get_num_tasks()];
+        for task_id in task_ids {
+            match run_set.get_mut(*task_id) {
+                Some(run) => *run = true,
+                None => return Err(ReplayError::UnknownTask { task_id: *task_id }),
+            }
+        }
+
+        let mut flow_copy = self.clone();
+        for task_id in task_ids {
+            for connection in self.get_input_connections(*task_id) {
+                let key = (connection.source_task_id, connection.source_port);
+                if run_set[connection.source_task_id]
+                    || flow_copy.injected_outputs.contains_key(&key)
+                {
+                    continue;
+                }
+
+                let missing_output = || ReplayError::MissingOutput {
+                    task_id: key.0,
+                    port: key.1,
+                };
+                let codec = self
+                    .output_codecs
+                    .iter()
+                    .find(|codec| (codec.task_id, codec.port) == key)
+                    .ok_or_else(missing_output)?;
+                let recorded = recording
+                    .get_output(key.0, key.1)
+                    .ok_or_else(missing_output)?;
+                let value = (codec.deserialize)(recorded.value.clone()).map_err(|error| {
+                    ReplayError::InvalidOutput {
+                        task_id: key.0,
+                        port: key.1,
+                        error,
+                    }
+                })?;
+                flow_copy.injected_outputs.insert(key, value);
+            }
+        }
+
+        // only the tasks the replayed ones depend on stand in through the recording
+        let mut replayed = vec![false; self.get_num_tasks()];
+        for task_id in task_ids {
+            for dep_task_id in self.dag.ancestors(*task_id) {
+                replayed[dep_task_id] = true;
+            }
+        }
+        let flow_exec = Execution::new(Arc::new(flow_copy)).with_run_set(&run_set, |task_id| {
+            if replayed[task_id] {
+                TaskStatus::Replayed
+            } else {
+                TaskStatus::Excluded
+            }
+        });
+        Ok(flow_exec.start_and_finish())
+    }
+
+    // Like `execute()`, but also returns a receiver of the events of the execution as they
+    // happen. The receiver is closed once the execution has finished.
+    pub fn execute_with_events(
+        &self,
+    ) -> (
+        impl Future<Output = Execution>,
+        UnboundedReceiver<ExecutionEvent>,
+    ) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let flow_copy = Arc::new(self.clone());
+        let flow_exec = Execution::new(flow_copy).with_events(sender);
+        (flow_exec.start_and_finish(), receiver)
+    }
 }
 
 impl Default for Flow {
@@ -1,4 +1,15 @@
+pub mod algorithm;
 pub mod dag;
+pub mod diff;
 pub mod execution;
 pub mod flow;
+pub mod map;
+pub mod observer;
+pub mod pipeline;
+pub mod profiler;
+#[cfg(feature = "serde")]
+pub mod registry;
+#[cfg(feature = "serde")]
+pub mod replay;
+pub mod report;
 pub mod task;
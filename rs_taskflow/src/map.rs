@@ -0,0 +1,163 @@
+use std::fmt::{Debug, Display};
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+
+use tokio::runtime::{Handle, RuntimeFlavor};
+use tokio::task;
+
+use crate::flow::Flow;
+use crate::task::*;
+
+type MapFuncType<T, U, E> = Arc<dyn Fn(&T) -> Result<U, E> + Send + Sync>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ElementError<E> {
+    // the map function returned an error for this element
+    Failed(E),
+    // the map function panicked on this element
+    Panicked(String),
+    // the element was cancelled before it finished, e.g. by a runtime shutting down
+    Cancelled,
+}
+
+impl<E: Display> Display for ElementError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ElementError::Failed(err) => write!(f, "element failed: {}", err),
+            ElementError::Panicked(msg) => write!(f, "element panicked: {}", msg),
+            ElementError::Cancelled => write!(f, "element cancelled"),
+        }
+    }
+}
+
+impl<E: Debug + Display> std::error::Error for ElementError<E> {}
+
+pub type MapResult<U, E> = Result<U, ElementError<E>>;
+
+//
+// Task that takes a collection, applies its function to every element and gathers the results.
+//
+// The elements are only dispatched when the task runs, so the amount of parallelism follows the
+// size of the input rather than the shape of the graph. The gathered output has one entry per
+// input element, in input order, and an element that fails or panics does not affect the others.
+//
+// The input is split into one chunk per worker of the runtime, each mapped by an executor task of its
+// own. The executor tasks outlive the borrow of the input, so every element is cloned once into
+// its chunk; inputs of elements that are expensive to clone are better passed as `Arc`s.
+//
+#[derive(Clone)]
+pub struct MapTask<T, U, E> {
+    input_handle: Option<TaskInputHandle<Vec<T>>>,
+    output: Option<Vec<MapResult<U, E>>>,
+    func: MapFuncType<T, U, E>,
+}
+
+impl<T, U, E> MapTask<T, U, E> {
+    pub fn new<F: 'static + Send + Sync + Fn(&T) -> Result<U, E>>(task_func: F) -> Self {
+        Self {
+            input_handle: None,
+            output: None,
+            func: Arc::new(task_func),
+        }
+    }
+}
+
+impl<T: 'static + Clone + Send + Sync, U: 'static + Send, E: 'static + Send> MapTask<T, U, E> {
+    //
+    // Only a multi-threaded runtime can lend the worker to block_in_place and run the elements
+    // elsewhere in the meantime; on any other runtime, or outside of one, the elements run one
+    // after the other on the calling thread.
+    //
+    fn run(&self, values: &[T]) -> Vec<MapResult<U, E>> {
+        match Handle::try_current() {
+            Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+                let num_chunks = handle.metrics().num_workers();
+                task::block_in_place(|| handle.block_on(self.dispatch(values, num_chunks)))
+            }
+            _ => values.iter().map(|x| run_element(&self.func, x)).collect(),
+        }
+    }
+
+    async fn dispatch(&self, values: &[T], num_chunks: usize) -> Vec<MapResult<U, E>> {
+        if values.is_empty() {
+            return Vec::new();
+        }
+        let chunk_size = values.len().div_ceil(num_chunks);
+        let join_handles: Vec<_> = values
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let func = self.func.clone();
+                let chunk = chunk.to_vec();
+                let chunk_len = chunk.len();
+                let join_handle = task::spawn(async move {
+                    chunk
+                        .iter()
+                        .map(|x| run_element(&func, x))
+                        .collect::<Vec<_>>()
+                });
+                (chunk_len, join_handle)
+            })
+            .collect();
+
+        let mut results = Vec::with_capacity(values.len());
+        for (chunk_len, join_handle) in join_handles {
+            match join_handle.await {
+                Ok(chunk_results) => results.extend(chunk_results),
+                Err(_) => results.extend((0..chunk_len).map(|_| Err(ElementError::Cancelled))),
+            }
+        }
+        results
+    }
+}
+
+// a panic is caught for each element, so that it only fails that element
+fn run_element<T, U, E>(func: &MapFuncType<T, U, E>, value: &T) -> MapResult<U, E> {
+    match panic::catch_unwind(AssertUnwindSafe(|| func(value))) {
+        Ok(result) => result.map_err(ElementError::Failed),
+        Err(payload) => Err(ElementError::Panicked(panic_message(payload))),
+    }
+}
+
+impl<
+        T: 'static + Clone + Send + Sync,
+        U: 'static + Clone + Send + Sync,
+        E: 'static + Clone + Send + Sync,
+    > TaskInput0<Vec<T>> for MapTask<T, U, E>
+{
+    fn set_input_0(&mut self, task_input: TaskInputHandle<Vec<T>>) {
+        self.input_handle = Some(task_input);
+    }
+}
+
+impl<
+        T: 'static + Clone + Send + Sync,
+        U: 'static + Clone + Send + Sync,
+        E: 'static + Clone + Send + Sync,
+    > TaskOutput0<Vec<MapResult<U, E>>> for MapTask<T, U, E>
+{
+    fn get_output_0(task: &dyn ExecutableTask) -> Option<&Vec<MapResult<U, E>>> {
+        task.as_any()
+            .downcast_ref::<Self>()
+            .unwrap()
+            .output
+            .as_ref()
+    }
+}
+
+impl<
+        T: 'static + Clone + Send + Sync,
+        U: 'static + Clone + Send + Sync,
+        E: 'static + Clone + Send + Sync,
+    > ExecutableTask for MapTask<T, U, E>
+{
+    fn exec(&mut self, flow: &Flow) {
+        match &self.input_handle {
+            Some(input) => {
+                let input_val = input.get_value(flow);
+                self.output = Some(self.run(input_val.unwrap()));
+            }
+            _ => panic!("input 0 is not connected"),
+        }
+    }
+}
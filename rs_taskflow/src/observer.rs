@@ -0,0 +1,26 @@
+use std::thread::ThreadId;
+use std::time::Instant;
+
+#[derive(Clone, Debug)]
+pub struct TaskInfo<'a> {
+    pub task_id: usize,
+    pub task_name: &'a str,
+    pub worker: ThreadId,
+}
+
+//
+// Callbacks invoked by the scheduler while a flow executes. Every method has an empty default
+// so an observer only implements the events it cares about.
+//
+// Observers are shared between all executions of the flow they are registered on, which may run
+// concurrently, and the task callbacks are called from whichever worker thread runs the task.
+//
+pub trait Observer: Send + Sync {
+    fn on_execution_start(&self, _num_tasks: usize, _time: Instant) {}
+
+    fn on_execution_end(&self, _time: Instant) {}
+
+    fn on_entry(&self, _task: &TaskInfo, _time: Instant) {}
+
+    fn on_exit(&self, _task: &TaskInfo, _start_time: Instant, _end_time: Instant) {}
+}
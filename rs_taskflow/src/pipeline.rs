@@ -0,0 +1,300 @@
+use std::any::Any;
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+use tokio::runtime::{Handle, RuntimeFlavor};
+use tokio::task;
+
+use crate::flow::Flow;
+use crate::task::*;
+
+type PipeItem = Box<dyn Any + Send>;
+type PipeItemIter = Box<dyn Iterator<Item = PipeItem> + Send>;
+type SourceFuncType<I> = Arc<dyn Fn(&I) -> PipeItemIter + Send + Sync>;
+type StageFuncType = Arc<dyn Fn(PipeItem) -> PipeItem + Send + Sync>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PipeType {
+    // items pass through the stage one at a time, in the order the source emitted them
+    Serial,
+    // any number of in-flight items may be inside the stage at once
+    Parallel,
+}
+
+#[derive(Clone)]
+struct Stage {
+    pipe_type: PipeType,
+    func: StageFuncType,
+}
+
+// A serial stage hands out turns in token order; a token waits until every earlier token has
+// left the stage. A stage that panicked is poisoned and hands out no more turns.
+struct StageTurn {
+    next_token: Mutex<Option<usize>>,
+    cond: Condvar,
+}
+
+impl StageTurn {
+    fn new() -> Self {
+        Self {
+            next_token: Mutex::new(Some(0)),
+            cond: Condvar::new(),
+        }
+    }
+
+    // returns false if the stage was poisoned while waiting
+    fn wait_for(&self, token: usize) -> bool {
+        let mut next_token = self.next_token.lock().unwrap();
+        loop {
+            match *next_token {
+                Some(next) if next == token => return true,
+                Some(_) => next_token = self.cond.wait(next_token).unwrap(),
+                None => return false,
+            }
+        }
+    }
+
+    fn advance(&self) {
+        if let Some(next_token) = self.next_token.lock().unwrap().as_mut() {
+            *next_token += 1;
+        }
+        self.cond.notify_all();
+    }
+
+    fn poison(&self) {
+        *self.next_token.lock().unwrap() = None;
+        self.cond.notify_all();
+    }
+}
+
+// stops the other tokens if the token holding it unwinds out of a stage
+struct AbortOnPanic<'a> {
+    aborted: &'a AtomicBool,
+    turns: &'a [StageTurn],
+}
+
+impl Drop for AbortOnPanic<'_> {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            self.aborted.store(true, Ordering::SeqCst);
+            for turn in self.turns {
+                turn.poison();
+            }
+        }
+    }
+}
+
+//
+// Task that streams the items produced by its source through a chain of stages.
+//
+// The source is pulled serially and every item pulled is tagged with a token. At most
+// `num_tokens` items are in flight at any time, and the collected output is in token order
+// regardless of how the parallel stages interleave.
+//
+#[derive(Clone)]
+pub struct Pipeline<I, O> {
+    num_tokens: usize,
+    source: SourceFuncType<I>,
+    stages: Vec<Stage>,
+    input_handle: Option<TaskInputHandle<I>>,
+    output: Option<Vec<O>>,
+}
+
+impl<I: 'static, O: 'static + Send> Pipeline<I, O> {
+    pub fn new<F, It>(num_tokens: usize, source_func: F) -> Self
+    where
+        F: 'static + Send + Sync + Fn(&I) -> It,
+        It: IntoIterator<Item = O>,
+        It::IntoIter: 'static + Send,
+    {
+        assert!(num_tokens > 0, "a pipeline needs at least one token");
+        Self {
+            num_tokens,
+            source: Arc::new(move |input: &I| -> PipeItemIter {
+                Box::new(
+                    source_func(input)
+                        .into_iter()
+                        .map(|item| Box::new(item) as PipeItem),
+                )
+            }),
+            stages: Vec::new(),
+            input_handle: None,
+            output: None,
+        }
+    }
+
+    pub fn add_stage<U: 'static + Send, F: 'static + Send + Sync + Fn(O) -> U>(
+        self,
+        pipe_type: PipeType,
+        stage_func: F,
+    ) -> Pipeline<I, U> {
+        let mut stages = self.stages;
+        stages.push(Stage {
+            pipe_type,
+            func: Arc::new(move |item: PipeItem| -> PipeItem {
+                Box::new(stage_func(*item.downcast::<O>().unwrap()))
+            }),
+        });
+
+        Pipeline {
+            num_tokens: self.num_tokens,
+            source: self.source,
+            stages,
+            input_handle: self.input_handle,
+            output: None,
+        }
+    }
+
+    pub fn get_num_tokens(&self) -> usize {
+        self.num_tokens
+    }
+
+    pub fn get_num_stages(&self) -> usize {
+        self.stages.len()
+    }
+
+    //
+    // Each token runs on tokio's blocking pool, since a token waiting for its turn at a serial
+    // stage blocks its thread; the pool caps how many threads all the pipelines together can
+    // start. Only a multi-threaded runtime can lend the worker to block_in_place while the tokens
+    // run; on any other runtime, or outside of one, a single token runs on the calling thread.
+    //
+    fn run(&self, input: &I) -> Vec<O> {
+        let state = Arc::new(PipeState::new((self.source)(input), self.stages.clone()));
+        match Handle::try_current() {
+            Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+                task::block_in_place(|| handle.block_on(run_tokens(self.num_tokens, &state)))
+            }
+            _ => state.run_token(),
+        }
+
+        let mut results = std::mem::take(&mut *state.results.lock().unwrap());
+        results.sort_unstable_by_key(|(token, _)| *token);
+        results
+            .into_iter()
+            .map(|(_, item)| *item.downcast::<O>().unwrap())
+            .collect()
+    }
+}
+
+// what the tokens of one run of a pipeline share
+struct PipeState {
+    source: Mutex<(PipeItemIter, usize)>,
+    stages: Vec<Stage>,
+    turns: Vec<StageTurn>,
+    results: Mutex<Vec<(usize, PipeItem)>>,
+    aborted: AtomicBool,
+}
+
+impl PipeState {
+    fn new(items: PipeItemIter, stages: Vec<Stage>) -> Self {
+        let turns = stages.iter().map(|_| StageTurn::new()).collect();
+        Self {
+            source: Mutex::new((items, 0)),
+            stages,
+            turns,
+            results: Mutex::new(Vec::new()),
+            aborted: AtomicBool::new(false),
+        }
+    }
+
+    // pulls items from the source and carries them through the stages until none are left
+    fn run_token(&self) {
+        loop {
+            if self.aborted.load(Ordering::SeqCst) {
+                break;
+            }
+            let _abort_guard = AbortOnPanic {
+                aborted: &self.aborted,
+                turns: &self.turns,
+            };
+
+            let (token, mut item) = {
+                let mut source_guard = self.source.lock().unwrap();
+                let (items, next_token) = &mut *source_guard;
+                match items.next() {
+                    Some(item) => {
+                        *next_token += 1;
+                        (*next_token - 1, item)
+                    }
+                    None => break,
+                }
+            };
+
+            for (stage, turn) in self.stages.iter().zip(self.turns.iter()) {
+                match stage.pipe_type {
+                    PipeType::Serial => {
+                        if !turn.wait_for(token) {
+                            return;
+                        }
+                        item = (stage.func)(item);
+                        turn.advance();
+                    }
+                    PipeType::Parallel => {
+                        item = (stage.func)(item);
+                    }
+                }
+            }
+
+            self.results.lock().unwrap().push((token, item));
+        }
+    }
+}
+
+// a panicking stage is re-raised here once every token has stopped
+async fn run_tokens(num_tokens: usize, state: &Arc<PipeState>) {
+    let join_handles: Vec<_> = (0..num_tokens)
+        .map(|_| {
+            let state = state.clone();
+            task::spawn_blocking(move || state.run_token())
+        })
+        .collect();
+
+    let mut payload = None;
+    for join_handle in join_handles {
+        match join_handle.await {
+            Ok(()) => {}
+            Err(err) if err.is_panic() => payload = payload.or(Some(err.into_panic())),
+            Err(_) => panic!("a pipeline token was cancelled"),
+        }
+    }
+    if let Some(payload) = payload {
+        panic::resume_unwind(payload);
+    }
+}
+
+impl<I: 'static + Clone + Send + Sync, O: 'static + Clone + Send + Sync> TaskInput0<I>
+    for Pipeline<I, O>
+{
+    fn set_input_0(&mut self, task_input: TaskInputHandle<I>) {
+        self.input_handle = Some(task_input);
+    }
+}
+
+impl<I: 'static + Clone + Send + Sync, O: 'static + Clone + Send + Sync> TaskOutput0<Vec<O>>
+    for Pipeline<I, O>
+{
+    fn get_output_0(task: &dyn ExecutableTask) -> Option<&Vec<O>> {
+        task.as_any()
+            .downcast_ref::<Self>()
+            .unwrap()
+            .output
+            .as_ref()
+    }
+}
+
+impl<I: 'static + Clone + Send + Sync, O: 'static + Clone + Send + Sync> ExecutableTask
+    for Pipeline<I, O>
+{
+    fn exec(&mut self, flow: &Flow) {
+        match &self.input_handle {
+            Some(input) => {
+                let input_val = input.get_value(flow);
+                self.output = Some(self.run(input_val.unwrap()));
+            }
+            _ => panic!("input 0 is not connected"),
+        }
+    }
+}
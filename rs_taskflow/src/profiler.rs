@@ -0,0 +1,144 @@
+use std::fs::File;
+use std::io;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::thread::ThreadId;
+use std::time::{Duration, Instant};
+
+use crate::observer::{Observer, TaskInfo};
+
+struct ProfiledTask {
+    task_id: usize,
+    task_name: String,
+    worker: usize,
+    start: Duration,
+    end: Duration,
+}
+
+fn write_json_string<W: Write>(writer: &mut W, value: &str) -> io::Result<()> {
+    write!(writer, "\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => write!(writer, "\\\"")?,
+            '\\' => write!(writer, "\\\\")?,
+            '\n' => write!(writer, "\\n")?,
+            '\r' => write!(writer, "\\r")?,
+            '\t' => write!(writer, "\\t")?,
+            c if c.is_control() => write!(writer, "\\u{:04x}", c as u32)?,
+            c => write!(writer, "{}", c)?,
+        }
+    }
+    write!(writer, "\"")
+}
+
+//
+// Observer that records when and on which worker thread every task ran, and writes the
+// recording in the Chrome Trace Event format understood by chrome://tracing and Perfetto.
+//
+// Times are relative to the creation of the profiler, and workers are numbered in the order in
+// which they first ran a task. A profiler registered on a flow records every execution of it.
+//
+pub struct Profiler {
+    origin: Instant,
+    workers: Mutex<Vec<ThreadId>>,
+    tasks: Mutex<Vec<ProfiledTask>>,
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+            workers: Mutex::new(Vec::new()),
+            tasks: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn get_num_recorded_tasks(&self) -> usize {
+        self.tasks.lock().unwrap().len()
+    }
+
+    pub fn get_num_workers(&self) -> usize {
+        self.workers.lock().unwrap().len()
+    }
+
+    pub fn clear(&self) {
+        self.workers.lock().unwrap().clear();
+        self.tasks.lock().unwrap().clear();
+    }
+
+    fn get_worker_index(&self, worker: ThreadId) -> usize {
+        let mut workers = self.workers.lock().unwrap();
+        match workers.iter().position(|w| *w == worker) {
+            Some(index) => index,
+            None => {
+                workers.push(worker);
+                workers.len() - 1
+            }
+        }
+    }
+
+    pub fn write_chrome_trace<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        // workers are counted from the recorded tasks, so that a task finishing meanwhile cannot
+        // be written with a worker that has no name
+        let tasks = self.tasks.lock().unwrap();
+        let num_workers = tasks.iter().map(|task| task.worker + 1).max().unwrap_or(0);
+
+        write!(writer, "{{\"traceEvents\":[")?;
+        for worker in 0..num_workers {
+            if worker > 0 {
+                write!(writer, ",")?;
+            }
+            write!(
+                writer,
+                "\n{{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":0,\"tid\":{},\"args\":{{\"name\":\"worker {}\"}}}}",
+                worker, worker
+            )?;
+        }
+        for (i, task) in tasks.iter().enumerate() {
+            if i > 0 || num_workers > 0 {
+                write!(writer, ",")?;
+            }
+            write!(writer, "\n{{\"name\":")?;
+            if task.task_name.is_empty() {
+                write_json_string(&mut writer, &format!("task {}", task.task_id))?;
+            } else {
+                write_json_string(&mut writer, &task.task_name)?;
+            }
+            write!(
+                writer,
+                ",\"cat\":\"task\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":0,\"tid\":{},\"args\":{{\"task_id\":{}}}}}",
+                task.start.as_micros(),
+                (task.end - task.start).as_micros(),
+                task.worker,
+                task.task_id
+            )?;
+        }
+        writeln!(writer, "\n],\"displayTimeUnit\":\"ms\"}}")
+    }
+
+    pub fn save_chrome_trace<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_chrome_trace(&mut writer)?;
+        writer.flush()
+    }
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Observer for Profiler {
+    fn on_exit(&self, task: &TaskInfo, start_time: Instant, end_time: Instant) {
+        let worker = self.get_worker_index(task.worker);
+        self.tasks.lock().unwrap().push(ProfiledTask {
+            task_id: task.task_id,
+            task_name: String::from(task.task_name),
+            worker,
+            start: start_time.saturating_duration_since(self.origin),
+            end: end_time.saturating_duration_since(self.origin),
+        });
+    }
+}
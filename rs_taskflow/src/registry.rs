@@ -0,0 +1,313 @@
+use std::any::{Any, TypeId};
+use std::fmt::Display;
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::dag::Dag;
+use crate::flow::{Connection, Flow, TaskHandle};
+use crate::task::*;
+
+type SaveFuncType =
+    Arc<dyn Fn(&dyn ExecutableTask) -> serde_json::Result<serde_json::Value> + Send + Sync>;
+type LoadFuncType =
+    Arc<dyn Fn(serde_json::Value) -> serde_json::Result<Box<dyn ExecutableTask>> + Send + Sync>;
+
+#[derive(Clone)]
+struct RegisteredTask {
+    kind: String,
+    type_id: TypeId,
+    save: SaveFuncType,
+    load: LoadFuncType,
+    get_output: fn(usize, usize) -> Option<ErasedOutput>,
+    set_input: fn(&mut dyn ExecutableTask, usize, &ErasedOutput) -> bool,
+}
+
+//
+// Maps task types to the kind names they are stored under and converts tasks from and to their
+// stored payloads, so that a flow can be written out with `Flow::to_description()` and rebuilt
+// with `FlowDescription::to_flow()`.
+//
+#[derive(Clone, Default)]
+pub struct TaskRegistry {
+    tasks: Vec<RegisteredTask>,
+}
+
+impl TaskRegistry {
+    pub fn new() -> Self {
+        Self { tasks: Vec::new() }
+    }
+
+    // a task type whose whole state is serializable
+    pub fn register<T: ErasedPorts + Serialize + DeserializeOwned>(&mut self, kind: &str) {
+        self.register_with(
+            kind,
+            |task: &T| serde_json::to_value(task),
+            serde_json::from_value,
+        );
+    }
+
+    //
+    // A task type that is saved and loaded by the given functions, e.g. a derived task whose
+    // function cannot be serialized and is instead restored by `load`.
+    //
+    pub fn register_with<T, S, L>(&mut self, kind: &str, save: S, load: L)
+    where
+        T: ErasedPorts,
+        S: Fn(&T) -> serde_json::Result<serde_json::Value> + Send + Sync + 'static,
+        L: Fn(serde_json::Value) -> serde_json::Result<T> + Send + Sync + 'static,
+    {
+        assert!(
+            self.tasks.iter().all(|task| task.kind != kind),
+            "task kind \"{}\" is already registered",
+            kind
+        );
+        if let Some(registered) = self
+            .tasks
+            .iter()
+            .find(|task| task.type_id == TypeId::of::<T>())
+        {
+            panic!(
+                "task type {} is already registered as kind \"{}\"",
+                std::any::type_name::<T>(),
+                registered.kind
+            );
+        }
+        self.tasks.push(RegisteredTask {
+            kind: String::from(kind),
+            type_id: TypeId::of::<T>(),
+            save: Arc::new(move |task| save(task.as_any().downcast_ref::<T>().unwrap())),
+            load: Arc::new(move |payload| Ok(Box::new(load(payload)?) as Box<dyn ExecutableTask>)),
+            get_output: T::get_erased_output,
+            set_input: |task, port, output| {
+                task.as_mut_any()
+                    .downcast_mut::<T>()
+                    .is_some_and(|task| task.set_erased_input(port, output))
+            },
+        });
+    }
+
+    pub fn get_kind(&self, task: &dyn ExecutableTask) -> Option<&str> {
+        self.find_by_type(task)
+            .map(|registered| registered.kind.as_str())
+    }
+
+    fn find_by_type(&self, task: &dyn ExecutableTask) -> Option<&RegisteredTask> {
+        let type_id = Any::type_id(task.as_any());
+        self.tasks
+            .iter()
+            .find(|registered| registered.type_id == type_id)
+    }
+
+    pub(crate) fn save_task(
+        &self,
+        task_id: usize,
+        task: &dyn ExecutableTask,
+    ) -> Result<(String, serde_json::Value), RegistryError> {
+        let registered = self
+            .find_by_type(task)
+            .ok_or(RegistryError::UnregisteredTask { task_id })?;
+        let payload =
+            (registered.save)(task).map_err(|error| RegistryError::UnserializableTask {
+                task_id,
+                kind: registered.kind.clone(),
+                error,
+            })?;
+        Ok((registered.kind.clone(), payload))
+    }
+
+    fn find_by_kind(&self, task_id: usize, kind: &str) -> Result<&RegisteredTask, RegistryError> {
+        self.tasks
+            .iter()
+            .find(|registered| registered.kind == kind)
+            .ok_or_else(|| RegistryError::UnknownKind {
+                task_id,
+                kind: String::from(kind),
+            })
+    }
+
+    fn load_task(
+        &self,
+        task_id: usize,
+        kind: &str,
+        payload: serde_json::Value,
+    ) -> Result<Box<dyn ExecutableTask>, RegistryError> {
+        let registered = self.find_by_kind(task_id, kind)?;
+        (registered.load)(payload).map_err(|error| RegistryError::InvalidPayload {
+            task_id,
+            kind: String::from(kind),
+            error,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskDescription {
+    pub name: String,
+    pub kind: String,
+    pub cluster: Option<usize>,
+    pub payload: serde_json::Value,
+}
+
+//
+// Serializable structure of a flow, see `Flow::to_description()`. The graph holds one node per
+// task, with the same ids as in the flow, and the connections tell which output port feeds which
+// input port. The edges of the graph follow from the connections, and a description whose edges
+// disagree with them is not loaded. Two descriptions compare equal when the flows were built the
+// same way.
+//
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlowDescription {
+    pub graph: Dag<TaskDescription>,
+    pub clusters: Vec<String>,
+    pub connections: Vec<Connection>,
+}
+
+impl FlowDescription {
+    //
+    // Rebuilds the flow with every task loaded by `registry` and connected as described, ready to
+    // be executed. Task ids, names and clusters are the same as in the described flow.
+    //
+    pub fn to_flow(&self, registry: &TaskRegistry) -> Result<Flow, RegistryError> {
+        let mut flow = Flow::new();
+        for label in self.clusters.iter() {
+            flow.add_cluster(label);
+        }
+
+        let num_tasks = self.graph.get_num_nodes();
+        let mut kinds = Vec::with_capacity(num_tasks);
+        for task_id in 0..num_tasks {
+            let node = self.graph.get_node(task_id);
+            let task = node.get_value();
+            let loaded = registry.load_task(task_id, &task.kind, task.payload.clone())?;
+            let task_handle = TaskHandle::<()>::from_id(flow.add_boxed_task(loaded));
+            flow.set_task_name(&task_handle, &task.name);
+            if let Some(cluster) = task.cluster {
+                if cluster >= self.clusters.len() {
+                    return Err(RegistryError::UnknownCluster { task_id, cluster });
+                }
+                flow.set_task_cluster(&task_handle, cluster);
+            }
+            kinds.push(registry.find_by_kind(task_id, &task.kind)?);
+        }
+
+        for connection in self.connections.iter() {
+            let invalid_connection = || RegistryError::InvalidConnection {
+                connection: *connection,
+            };
+            if connection.source_task_id >= num_tasks || connection.target_task_id >= num_tasks {
+                return Err(invalid_connection());
+            }
+            let output = (kinds[connection.source_task_id].get_output)(
+                connection.source_task_id,
+                connection.source_port,
+            )
+            .ok_or_else(invalid_connection)?;
+            let set_input = kinds[connection.target_task_id].set_input;
+            if !flow.connect_erased(*connection, &output, set_input) {
+                return Err(invalid_connection());
+            }
+        }
+
+        let flow_graph = flow.get_flow_graph();
+        for task_id in 0..num_tasks {
+            if flow_graph.get_all_of_dependencies(task_id)
+                != self.graph.get_all_of_dependencies(task_id)
+                || flow_graph.get_any_of_dependencies(task_id)
+                    != self.graph.get_any_of_dependencies(task_id)
+            {
+                return Err(RegistryError::MismatchedEdges { task_id });
+            }
+        }
+        Ok(flow)
+    }
+}
+
+#[derive(Debug)]
+pub enum RegistryError {
+    // the type of the task was not registered
+    UnregisteredTask {
+        task_id: usize,
+    },
+    // no task type was registered under the kind of the described task
+    UnknownKind {
+        task_id: usize,
+        kind: String,
+    },
+    // the payload of the described task could not be loaded
+    InvalidPayload {
+        task_id: usize,
+        kind: String,
+        error: serde_json::Error,
+    },
+    // the state of the task could not be saved as a payload
+    UnserializableTask {
+        task_id: usize,
+        kind: String,
+        error: serde_json::Error,
+    },
+    // the described task belongs to a cluster that is not described
+    UnknownCluster {
+        task_id: usize,
+        cluster: usize,
+    },
+    // the ports of the described connection do not exist, have different types, or the input
+    // does not take another producer
+    InvalidConnection {
+        connection: Connection,
+    },
+    // the edges of the graph into the described task do not match the connections to it
+    MismatchedEdges {
+        task_id: usize,
+    },
+}
+
+impl Display for RegistryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegistryError::UnregisteredTask { task_id } => {
+                write!(f, "the type of task {} is not registered", task_id)
+            }
+            RegistryError::UnknownKind { task_id, kind } => {
+                write!(f, "task {} has unknown kind \"{}\"", task_id, kind)
+            }
+            RegistryError::InvalidPayload {
+                task_id,
+                kind,
+                error,
+            } => write!(
+                f,
+                "payload of task {} of kind \"{}\" is invalid: {}",
+                task_id, kind, error
+            ),
+            RegistryError::UnserializableTask {
+                task_id,
+                kind,
+                error,
+            } => write!(
+                f,
+                "task {} of kind \"{}\" cannot be saved: {}",
+                task_id, kind, error
+            ),
+            RegistryError::UnknownCluster { task_id, cluster } => {
+                write!(f, "task {} is in unknown cluster {}", task_id, cluster)
+            }
+            RegistryError::InvalidConnection { connection } => write!(
+                f,
+                "output {} of task {} cannot be connected to input {} of task {}",
+                connection.source_port,
+                connection.source_task_id,
+                connection.target_port,
+                connection.target_task_id
+            ),
+            RegistryError::MismatchedEdges { task_id } => write!(
+                f,
+                "the graph edges into task {} do not match its connections",
+                task_id
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
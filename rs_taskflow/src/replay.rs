@@ -0,0 +1,152 @@
+use std::any::Any;
+use std::fmt::Display;
+use std::fs::File;
+use std::io;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::task::*;
+
+type SerializeFuncType =
+    Arc<dyn Fn(&dyn ExecutableTask) -> Option<serde_json::Result<serde_json::Value>> + Send + Sync>;
+type DeserializeFuncType =
+    Arc<dyn Fn(serde_json::Value) -> serde_json::Result<Arc<dyn Any + Send + Sync>> + Send + Sync>;
+
+// converts one output port of one task from and to its recorded form
+#[derive(Clone)]
+pub(crate) struct OutputCodec {
+    pub(crate) task_id: usize,
+    pub(crate) port: usize,
+    pub(crate) serialize: SerializeFuncType,
+    pub(crate) deserialize: DeserializeFuncType,
+}
+
+impl OutputCodec {
+    pub(crate) fn new<T: 'static + Serialize + DeserializeOwned + Send + Sync>(
+        task_id: usize,
+        port: usize,
+        get_output: fn(&dyn ExecutableTask) -> Option<&T>,
+    ) -> Self {
+        Self {
+            task_id,
+            port,
+            serialize: Arc::new(move |task| get_output(task).map(serde_json::to_value)),
+            deserialize: Arc::new(|value| {
+                let value: T = serde_json::from_value(value)?;
+                Ok(Arc::new(value) as Arc<dyn Any + Send + Sync>)
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedOutput {
+    pub task_id: usize,
+    pub task_name: String,
+    pub port: usize,
+    pub value: serde_json::Value,
+}
+
+//
+// Output values of the recorded ports of an execution, see `Flow::record_output0()` and friends
+// and `Execution::get_recording()`. Tasks are identified by id, so a recording can only be
+// replayed on a flow built the same way as the one it was recorded from.
+//
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    outputs: Vec<RecordedOutput>,
+}
+
+impl Recording {
+    pub(crate) fn new(outputs: Vec<RecordedOutput>) -> Self {
+        Self { outputs }
+    }
+
+    pub fn get_outputs(&self) -> &[RecordedOutput] {
+        &self.outputs
+    }
+
+    pub fn get_output(&self, task_id: usize, port: usize) -> Option<&RecordedOutput> {
+        self.outputs
+            .iter()
+            .find(|o| o.task_id == task_id && o.port == port)
+    }
+
+    pub fn write<W: Write>(&self, writer: W) -> serde_json::Result<()> {
+        serde_json::to_writer_pretty(writer, self)
+    }
+
+    pub fn read<R: Read>(reader: R) -> serde_json::Result<Self> {
+        serde_json::from_reader(reader)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self::read(BufReader::new(File::open(path)?))?)
+    }
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    // the task id is not one of the tasks of the flow
+    UnknownTask {
+        task_id: usize,
+    },
+    // a replayed task reads an output that is neither recorded nor produced by a replayed task
+    MissingOutput {
+        task_id: usize,
+        port: usize,
+    },
+    // the recorded value does not match the type of the output port
+    InvalidOutput {
+        task_id: usize,
+        port: usize,
+        error: serde_json::Error,
+    },
+    // the value of the output port could not be serialized when recording it
+    UnserializableOutput {
+        task_id: usize,
+        port: usize,
+        error: serde_json::Error,
+    },
+}
+
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplayError::UnknownTask { task_id } => write!(f, "task {} does not exist", task_id),
+            ReplayError::MissingOutput { task_id, port } => {
+                write!(f, "output {} of task {} was not recorded", port, task_id)
+            }
+            ReplayError::InvalidOutput {
+                task_id,
+                port,
+                error,
+            } => write!(
+                f,
+                "recorded output {} of task {} is invalid: {}",
+                port, task_id, error
+            ),
+            ReplayError::UnserializableOutput {
+                task_id,
+                port,
+                error,
+            } => write!(
+                f,
+                "output {} of task {} cannot be recorded: {}",
+                port, task_id, error
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
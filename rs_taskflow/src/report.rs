@@ -0,0 +1,209 @@
+use std::fmt;
+use std::thread::ThreadId;
+use std::time::{Duration, Instant};
+
+use crate::flow::Flow;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TaskTiming {
+    pub task_id: usize,
+    pub task_name: String,
+    pub start: Duration, // relative to the start of the execution
+    pub duration: Duration,
+    pub worker: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorkerUtilisation {
+    pub worker: usize,
+    pub num_tasks: usize,
+    pub busy_time: Duration,
+    pub utilisation: f64, // fraction of the total time spent running tasks
+}
+
+//
+// Timing statistics of a finished execution, see `Execution::get_report()`.
+//
+// Only tasks that ran are reported. Workers are numbered in the order in which they started their
+// first task. The critical path only follows dependencies that had completed by the time the
+// dependant started, so a `first_completed` input contributes the producer that was waited for.
+//
+#[derive(Clone, Debug)]
+pub struct ExecutionReport {
+    total_time: Duration,
+    task_timings: Vec<TaskTiming>, // ordered by start time
+    worker_utilisations: Vec<WorkerUtilisation>,
+    critical_path: Vec<usize>,
+    critical_path_time: Duration,
+}
+
+impl ExecutionReport {
+    pub(crate) fn new(
+        flow: &Flow,
+        start_time: Instant,
+        end_time: Instant,
+        task_timings: &[Option<(Instant, Instant, ThreadId)>],
+    ) -> Self {
+        let total_time = end_time.saturating_duration_since(start_time);
+
+        let mut ran: Vec<(usize, Instant, Instant, ThreadId)> = task_timings
+            .iter()
+            .enumerate()
+            .filter_map(|(task_id, timing)| timing.map(|(s, e, w)| (task_id, s, e, w)))
+            .collect();
+        ran.sort_by_key(|(task_id, task_start, _, _)| (*task_start, *task_id));
+
+        let mut workers = Vec::<ThreadId>::new();
+        let mut worker_utilisations = Vec::<WorkerUtilisation>::new();
+        let mut timings = Vec::with_capacity(ran.len());
+        for (task_id, task_start, task_end, worker_id) in ran.iter() {
+            let worker = match workers.iter().position(|w| w == worker_id) {
+                Some(worker) => worker,
+                None => {
+                    workers.push(*worker_id);
+                    worker_utilisations.push(WorkerUtilisation {
+                        worker: workers.len() - 1,
+                        num_tasks: 0,
+                        busy_time: Duration::ZERO,
+                        utilisation: 0.0,
+                    });
+                    workers.len() - 1
+                }
+            };
+            let duration = task_end.saturating_duration_since(*task_start);
+            worker_utilisations[worker].num_tasks += 1;
+            worker_utilisations[worker].busy_time += duration;
+            timings.push(TaskTiming {
+                task_id: *task_id,
+                task_name: String::from(flow.get_task_name(*task_id)),
+                start: task_start.saturating_duration_since(start_time),
+                duration,
+                worker,
+            });
+        }
+        for worker in worker_utilisations.iter_mut() {
+            if !total_time.is_zero() {
+                worker.utilisation = worker.busy_time.as_secs_f64() / total_time.as_secs_f64();
+            }
+        }
+
+        // longest chain of dependent tasks ending at each task, visiting tasks in start order so
+        // that every dependency that completed before a task started has already been visited
+        let mut path_times = vec![Duration::ZERO; task_timings.len()];
+        let mut path_predecessors = vec![None; task_timings.len()];
+        for (task_id, task_start, task_end, _) in ran.iter() {
+            let predecessor = flow
+                .get_flow_graph()
+                .get_dependencies(*task_id)
+                .iter()
+                .filter(|dep_id| match task_timings[**dep_id] {
+                    Some((_, dep_end, _)) => dep_end <= *task_start,
+                    None => false,
+                })
+                .max_by_key(|dep_id| (path_times[**dep_id], usize::MAX - **dep_id));
+            let predecessor_time = predecessor.map_or(Duration::ZERO, |dep_id| path_times[*dep_id]);
+            path_times[*task_id] =
+                predecessor_time + task_end.saturating_duration_since(*task_start);
+            path_predecessors[*task_id] = predecessor.copied();
+        }
+
+        let mut critical_path = Vec::new();
+        let mut critical_path_time = Duration::ZERO;
+        if let Some((last_task_id, _, _, _)) = ran
+            .iter()
+            .max_by_key(|(task_id, _, _, _)| (path_times[*task_id], usize::MAX - *task_id))
+        {
+            critical_path_time = path_times[*last_task_id];
+            let mut task_id = Some(*last_task_id);
+            while let Some(id) = task_id {
+                critical_path.push(id);
+                task_id = path_predecessors[id];
+            }
+            critical_path.reverse();
+        }
+
+        Self {
+            total_time,
+            task_timings: timings,
+            worker_utilisations,
+            critical_path,
+            critical_path_time,
+        }
+    }
+
+    pub fn get_total_time(&self) -> Duration {
+        self.total_time
+    }
+
+    pub fn get_task_timings(&self) -> &[TaskTiming] {
+        &self.task_timings
+    }
+
+    pub fn get_task_timing(&self, task_id: usize) -> Option<&TaskTiming> {
+        self.task_timings.iter().find(|t| t.task_id == task_id)
+    }
+
+    pub fn get_worker_utilisations(&self) -> &[WorkerUtilisation] {
+        &self.worker_utilisations
+    }
+
+    pub fn get_critical_path(&self) -> &[usize] {
+        &self.critical_path
+    }
+
+    pub fn get_critical_path_time(&self) -> Duration {
+        self.critical_path_time
+    }
+
+    fn get_task_label(&self, task_id: usize) -> String {
+        match self.get_task_timing(task_id) {
+            Some(timing) if !timing.task_name.is_empty() => {
+                format!("{} ({})", timing.task_name, task_id)
+            }
+            _ => format!("task {}", task_id),
+        }
+    }
+}
+
+impl fmt::Display for ExecutionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "total time: {:?}", self.total_time)?;
+
+        let critical_path: Vec<String> = self
+            .critical_path
+            .iter()
+            .map(|task_id| self.get_task_label(*task_id))
+            .collect();
+        writeln!(
+            f,
+            "critical path ({:?}): {}",
+            self.critical_path_time,
+            critical_path.join(" -> ")
+        )?;
+
+        writeln!(f, "tasks:")?;
+        for timing in self.task_timings.iter() {
+            writeln!(
+                f,
+                "  {}: started at {:?}, took {:?} on worker {}",
+                self.get_task_label(timing.task_id),
+                timing.start,
+                timing.duration,
+                timing.worker
+            )?;
+        }
+
+        writeln!(f, "workers:")?;
+        for worker in self.worker_utilisations.iter() {
+            writeln!(
+                f,
+                "  worker {}: {} tasks, busy for {:?} ({:.1}%)",
+                worker.worker,
+                worker.num_tasks,
+                worker.busy_time,
+                worker.utilisation * 100.0
+            )?;
+        }
+        Ok(())
+    }
+}
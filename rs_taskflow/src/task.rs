@@ -1,4 +1,5 @@
 use dyn_clone::DynClone;
+use std::any::Any;
 use std::fmt::Debug;
 use std::marker::Send;
 
@@ -23,13 +24,47 @@ mod private {
     }
 }
 
+pub(crate) fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        msg.to_string()
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        String::from("unknown panic payload")
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JoinPolicy {
+    // connecting a second producer to the input replaces the first one
+    Replace,
+    // connecting a second producer to the input is an error
+    Unique,
+    // the task waits for every producer and receives all of their values, in connection order
+    Collect,
+    // the task runs as soon as one producer has completed and receives that producer's value
+    FirstCompleted,
+}
+
 pub trait ExecutableTask: AsAny + DynClone + Sync + Send {
     fn exec(&mut self, flow: &Flow);
+
+    fn get_input_join_policy(&self, _port: usize) -> JoinPolicy {
+        JoinPolicy::Replace
+    }
+
+    // a task that ran without panicking but failed describes its error here
+    fn get_error_message(&self) -> Option<String> {
+        None
+    }
+
+    // before the task runs, each `FirstCompleted` input is told which producer the task waited for
+    fn set_input_producer(&mut self, _port: usize, _producer_id: usize) {}
 }
 
 impl PartialEq for dyn ExecutableTask {
     fn eq(&self, other: &Self) -> bool {
-        self == other
+        std::ptr::addr_eq(self, other)
     }
 }
 
@@ -66,9 +101,136 @@ impl ExecutableTask for DummyTask {
     }
 }
 
+//
+// Tasks whose ports have names, declared as `name: Type` in `derive_task`. The generated `Inputs`
+// and `Outputs` structs hold one port per named input or output, reachable from a task handle
+// with `inputs()` and `outputs()` and connected with `Flow::connect_ports()`.
+//
+pub trait TaskPorts: ExecutableTask + Sized {
+    type Inputs;
+    type Outputs;
+
+    fn get_inputs(task_id: usize) -> Self::Inputs;
+    fn get_outputs(task_id: usize) -> Self::Outputs;
+}
+
+//
+// Tasks whose function returns a `Result`, declared with `error = Type` in `derive_task`. When the
+// function returns an error the task has no outputs, it is reported as failed and its error can be
+// read back with `Execution::get_task_error()`.
+//
+pub trait FallibleTask: ExecutableTask {
+    type Error;
+
+    fn get_error(task: &dyn ExecutableTask) -> Option<&Self::Error>;
+}
+
+//
+// Tasks whose ports can be connected without naming the port types, which is how a flow is rebuilt
+// from its description, see `FlowDescription::to_flow()`. `derive_task` implements it.
+//
+pub trait ErasedPorts: ExecutableTask {
+    fn get_erased_output(task_id: usize, port: usize) -> Option<ErasedOutput>;
+
+    // returns false if the task has no such input or the output has another type
+    fn set_erased_input(&mut self, port: usize, output: &ErasedOutput) -> bool;
+}
+
+// an output port of a task whose type is only checked when it is connected
+pub struct ErasedOutput {
+    task_id: usize,
+    port: usize,
+    type_name: &'static str,
+    output_func: Box<dyn Any + Send + Sync>,
+}
+
+impl ErasedOutput {
+    pub fn new<T: 'static>(
+        task_id: usize,
+        port: usize,
+        func: fn(&dyn ExecutableTask) -> Option<&T>,
+    ) -> Self {
+        Self {
+            task_id,
+            port,
+            type_name: std::any::type_name::<T>(),
+            output_func: Box::new(func),
+        }
+    }
+
+    pub fn get_type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    // the handle to read the output from an input of type `T`, if the output has that type
+    pub fn get_input_handle<T: 'static>(&self) -> Option<TaskInputHandle<T>> {
+        let func = self
+            .output_func
+            .downcast_ref::<fn(&dyn ExecutableTask) -> Option<&T>>()?;
+        Some(TaskInputHandle::new(self.task_id, *func).with_source_port(self.port))
+    }
+}
+
+pub struct OutputPort<T> {
+    task_id: usize,
+    port: usize,
+    output_func: fn(&dyn ExecutableTask) -> Option<&T>,
+}
+
+impl<T> OutputPort<T> {
+    pub fn new(task_id: usize, port: usize, func: fn(&dyn ExecutableTask) -> Option<&T>) -> Self {
+        Self {
+            task_id,
+            port,
+            output_func: func,
+        }
+    }
+
+    pub fn get_task_id(&self) -> usize {
+        self.task_id
+    }
+
+    pub fn get_port(&self) -> usize {
+        self.port
+    }
+
+    pub(crate) fn get_output_func(&self) -> fn(&dyn ExecutableTask) -> Option<&T> {
+        self.output_func
+    }
+}
+
+pub struct InputPort<B, T> {
+    task_id: usize,
+    port: usize,
+    input_func: fn(&mut B, TaskInputHandle<T>),
+}
+
+impl<B, T> InputPort<B, T> {
+    pub fn new(task_id: usize, port: usize, func: fn(&mut B, TaskInputHandle<T>)) -> Self {
+        Self {
+            task_id,
+            port,
+            input_func: func,
+        }
+    }
+
+    pub fn get_task_id(&self) -> usize {
+        self.task_id
+    }
+
+    pub fn get_port(&self) -> usize {
+        self.port
+    }
+
+    pub(crate) fn get_input_func(&self) -> fn(&mut B, TaskInputHandle<T>) {
+        self.input_func
+    }
+}
+
 #[derive(Clone)]
 pub struct TaskInputHandle<T> {
     source_task_id: usize,
+    source_port: usize,
     value_func: fn(&dyn ExecutableTask) -> Option<&T>,
 }
 
@@ -76,16 +238,36 @@ impl<T> TaskInputHandle<T> {
     pub fn new(id: usize, func: fn(&dyn ExecutableTask) -> Option<&T>) -> Self {
         Self {
             source_task_id: id,
+            source_port: 0,
             value_func: func,
         }
     }
 
+    pub fn with_source_port(mut self, port: usize) -> Self {
+        self.source_port = port;
+        self
+    }
+
     pub fn set(&mut self, id: usize, func: fn(&dyn ExecutableTask) -> Option<&T>) {
         self.source_task_id = id;
+        self.source_port = 0;
         self.value_func = func;
     }
 
-    pub fn get_value<'a, 'b>(&'a self, flow: &'b Flow) -> Option<&'b T> {
+    pub fn get_source_task_id(&self) -> usize {
+        self.source_task_id
+    }
+
+    pub fn get_source_port(&self) -> usize {
+        self.source_port
+    }
+}
+
+impl<T: 'static> TaskInputHandle<T> {
+    pub fn get_value<'b>(&self, flow: &'b Flow) -> Option<&'b T> {
+        if let Some(value) = flow.get_injected_output(self.source_task_id, self.source_port) {
+            return value.downcast_ref::<T>();
+        }
         let task_handle = flow.get_task_by_id::<DummyTask>(self.source_task_id); // calling task_handle.borrow_concrete() will panic
         let val_ref = (self.value_func)(task_handle.borrow());
         let val_ptr: *const T = val_ref.unwrap();
@@ -97,6 +279,7 @@ impl<T> Debug for TaskInputHandle<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("TaskInputHandle")
             .field("source_task_id", &self.source_task_id)
+            .field("source_port", &self.source_port)
             .field(
                 "value_func",
                 &format_args!("{:p}", self.value_func as *const ()),
@@ -0,0 +1,151 @@
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+
+use rs_taskflow::algorithm::{self, Partitioner};
+use rs_taskflow::flow::Flow;
+use rs_taskflow::task::*;
+
+#[rs_taskflow_derive::derive_task((), (Vec<i64>,))]
+struct InputTask;
+
+#[rs_taskflow_derive::derive_task((), (Vec<(usize, i64)>,))]
+struct PieceTask;
+
+fn input_values() -> Vec<i64> {
+    (0..103).map(|x| (x * 37) % 101 - 50).collect()
+}
+
+#[test]
+fn partitioner_covers_input() {
+    for partitioner in [
+        Partitioner::new(1),
+        Partitioner::new(4),
+        Partitioner::new(200),
+        Partitioner::new(3).with_chunk_size(1),
+        Partitioner::new(3).with_chunk_size(7),
+    ] {
+        for len in [0, 1, 5, 103] {
+            let mut covered: Vec<usize> = (0..partitioner.get_num_tasks())
+                .flat_map(|i| partitioner.get_pieces(len, i))
+                .flatten()
+                .collect();
+            covered.sort_unstable();
+            assert_eq!(covered, (0..len).collect::<Vec<usize>>());
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn main() {
+    let visited_sum = Arc::new(AtomicI64::new(0));
+
+    //
+    // declare system
+    //
+    let mut flow = Flow::new();
+
+    let input_task = flow.add_new_task(InputTask::new(input_values));
+
+    let visited_sum_ref = visited_sum.clone();
+    let for_each = algorithm::for_each(&mut flow, Partitioner::new(4), move |x: &i64| {
+        visited_sum_ref.fetch_add(*x, Ordering::Relaxed);
+    });
+    let transform = algorithm::transform(&mut flow, Partitioner::new(3), |x: &i64| {
+        (x * 2).to_string()
+    });
+    let reduce = algorithm::reduce(
+        &mut flow,
+        Partitioner::new(4).with_chunk_size(5),
+        1000,
+        |a: &i64, b: &i64| a + b,
+    );
+    let scan = algorithm::inclusive_scan(
+        &mut flow,
+        Partitioner::new(3).with_chunk_size(8),
+        |a: &i64, b: &i64| a + b,
+    );
+    let sort = algorithm::sort::<i64>(&mut flow, Partitioner::new(5));
+    let sort_desc = algorithm::sort_by(&mut flow, Partitioner::new(2), |a: &i64, b: &i64| b.cmp(a));
+
+    flow.connect_output0_to_input0(&input_task, &for_each.entry);
+    flow.connect_output0_to_input0(&input_task, &transform.entry);
+    flow.connect_output0_to_input0(&input_task, &reduce.entry);
+    flow.connect_output0_to_input0(&input_task, &scan.entry);
+    flow.connect_output0_to_input0(&input_task, &sort.entry);
+    flow.connect_output0_to_input0(&input_task, &sort_desc.entry);
+
+    //
+    // starting running the system
+    //
+    let flow_exec = flow.execute().await;
+
+    //
+    // check the results of the system
+    //
+    let values = input_values();
+
+    assert!(flow_exec.get_task_output0(&for_each.exit).is_some());
+    assert_eq!(
+        visited_sum.load(Ordering::Relaxed),
+        values.iter().sum::<i64>()
+    );
+
+    let expected: Vec<String> = values.iter().map(|x| (x * 2).to_string()).collect();
+    assert_eq!(
+        *flow_exec.get_task_output0(&transform.exit).unwrap(),
+        expected
+    );
+
+    let expected: i64 = 1000 + values.iter().sum::<i64>();
+    assert_eq!(*flow_exec.get_task_output0(&reduce.exit).unwrap(), expected);
+
+    let expected: Vec<i64> = values
+        .iter()
+        .scan(0, |acc, x| {
+            *acc += x;
+            Some(*acc)
+        })
+        .collect();
+    assert_eq!(*flow_exec.get_task_output0(&scan.exit).unwrap(), expected);
+
+    let mut expected = values.clone();
+    expected.sort();
+    assert_eq!(*flow_exec.get_task_output0(&sort.exit).unwrap(), expected);
+
+    expected.reverse();
+    assert_eq!(
+        *flow_exec.get_task_output0(&sort_desc.exit).unwrap(),
+        expected
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn reconnect_gather() {
+    //
+    // declare system
+    //
+    let mut flow = Flow::new();
+
+    let input_task = flow.add_new_task(InputTask::new(|| vec![1, 2, 3]));
+    let piece_task = flow.add_new_task(PieceTask::new(|| vec![(3, 100)]));
+    let reduce = algorithm::reduce(&mut flow, Partitioner::new(2), 0, |a: &i64, b: &i64| a + b);
+
+    flow.connect_output0_to_input0(&input_task, &reduce.entry);
+    flow.connect_output0_to_input0(&piece_task, &reduce.exit);
+    let num_connections = flow.get_input_connections(reduce.exit.id()).len();
+    flow.connect_output0_to_input0(&piece_task, &reduce.exit);
+    assert_eq!(
+        flow.get_input_connections(reduce.exit.id()).len(),
+        num_connections
+    );
+
+    //
+    // starting running the system
+    //
+    let flow_exec = flow.execute().await;
+
+    //
+    // check the results of the system, which count the extra piece once
+    //
+    assert_eq!(*flow_exec.get_task_output0(&reduce.exit).unwrap(), 106);
+}
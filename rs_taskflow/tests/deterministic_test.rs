@@ -0,0 +1,107 @@
+use rs_taskflow::execution::ExecutionEvent;
+use rs_taskflow::flow::Flow;
+use rs_taskflow::task::*;
+
+#[rs_taskflow_derive::derive_task((), (i32,))]
+struct SourceTask;
+
+#[rs_taskflow_derive::derive_task((i32,), (i32,))]
+struct ForwardTask;
+
+#[rs_taskflow_derive::derive_task((i32, i32), (i32,))]
+struct SumTask;
+
+fn build_flow() -> Flow {
+    let mut flow = Flow::new();
+
+    let source = flow.add_new_task(SourceTask::new(|| 1));
+    let left = flow.add_new_task(ForwardTask::new(|x: &i32| x + 1));
+    let bad = flow.add_new_task(ForwardTask::new(|_: &i32| -> i32 { panic!("bad input") }));
+    let right = flow.add_new_task(ForwardTask::new(|x: &i32| x + 2));
+    let sum = flow.add_new_task(SumTask::new(|a: &i32, b: &i32| a + b));
+    let after_bad_1 = flow.add_new_task(ForwardTask::new(|x: &i32| x + 1));
+    let after_bad_2 = flow.add_new_task(ForwardTask::new(|x: &i32| x + 1));
+
+    flow.connect_output0_to_input0(&source, &left);
+    flow.connect_output0_to_input0(&source, &bad);
+    flow.connect_output0_to_input0(&source, &right);
+    flow.connect_output0_to_input0(&left, &sum);
+    flow.connect_output0_to_input1(&right, &sum);
+    flow.connect_output0_to_input0(&bad, &after_bad_1);
+    flow.connect_output0_to_input0(&bad, &after_bad_2);
+
+    flow.set_deterministic(true);
+    flow.set_single_worker(true);
+    flow
+}
+
+async fn collect_events(flow: &Flow) -> Vec<ExecutionEvent> {
+    let (flow_exec, mut events) = flow.execute_with_events();
+    let collector = tokio::spawn(async move {
+        let mut received = Vec::new();
+        while let Some(event) = events.recv().await {
+            received.push(event);
+        }
+        received
+    });
+    let flow_exec = flow_exec.await;
+    if flow.is_single_worker() {
+        assert_eq!(flow_exec.get_report().get_worker_utilisations().len(), 1);
+    }
+    collector.await.unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn main() {
+    let flow = build_flow();
+    assert!(flow.is_deterministic());
+    assert!(flow.is_single_worker());
+
+    let expected = vec![
+        ExecutionEvent::Ready(0),
+        ExecutionEvent::Started(0),
+        ExecutionEvent::Finished(0),
+        ExecutionEvent::Ready(1),
+        ExecutionEvent::Ready(2),
+        ExecutionEvent::Ready(3),
+        ExecutionEvent::Started(1),
+        ExecutionEvent::Finished(1),
+        ExecutionEvent::Started(2),
+        ExecutionEvent::Failed(2, String::from("bad input")),
+        ExecutionEvent::Skipped(5),
+        ExecutionEvent::Skipped(6),
+        ExecutionEvent::Started(3),
+        ExecutionEvent::Finished(3),
+        ExecutionEvent::Ready(4),
+        ExecutionEvent::Started(4),
+        ExecutionEvent::Finished(4),
+    ];
+    for _ in 0..20 {
+        assert_eq!(collect_events(&flow).await, expected);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn parallel() {
+    let mut flow = build_flow();
+    flow.set_single_worker(false);
+
+    //
+    // the tasks run in parallel, but the dependants of the source still become ready in id order
+    //
+    for _ in 0..20 {
+        let ready: Vec<ExecutionEvent> = collect_events(&flow)
+            .await
+            .into_iter()
+            .filter(|event| matches!(event, ExecutionEvent::Ready(1..=3)))
+            .collect();
+        assert_eq!(
+            ready,
+            [
+                ExecutionEvent::Ready(1),
+                ExecutionEvent::Ready(2),
+                ExecutionEvent::Ready(3)
+            ]
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use rs_taskflow::diff::FlowChange;
+use rs_taskflow::flow::Flow;
+use rs_taskflow::task::*;
+
+#[rs_taskflow_derive::derive_task((), (i32,))]
+struct SourceTask;
+
+#[rs_taskflow_derive::derive_task((), (i64,))]
+struct WideSourceTask;
+
+#[rs_taskflow_derive::derive_task((i32,), (i32,))]
+struct ForwardTask;
+
+#[rs_taskflow_derive::derive_task((i64,), (i32,))]
+struct NarrowTask;
+
+#[rs_taskflow_derive::derive_task((i32, i32), (i32,))]
+struct SumTask;
+
+#[test]
+fn main() {
+    //
+    // source -> double -> sum <- triple <- source
+    //
+    let mut old_flow = Flow::new();
+    let source = old_flow.add_new_task(SourceTask::new(|| 1));
+    let double = old_flow.add_new_task(ForwardTask::new(|x: &i32| x * 2));
+    let triple = old_flow.add_new_task(ForwardTask::new(|x: &i32| x * 3));
+    let sum = old_flow.add_new_task(SumTask::new(|a: &i32, b: &i32| a + b));
+    old_flow.set_task_name(&source, "source");
+    old_flow.set_task_name(&double, "double");
+    old_flow.set_task_name(&triple, "triple");
+    old_flow.set_task_name(&sum, "sum");
+    old_flow.connect_output0_to_input0(&source, &double);
+    old_flow.connect_output0_to_input0(&source, &triple);
+    old_flow.connect_output0_to_input0(&double, &sum);
+    old_flow.connect_output0_to_input1(&triple, &sum);
+
+    assert!(old_flow.diff(&old_flow.clone()).is_empty());
+    assert_eq!(old_flow.diff(&old_flow).to_string(), "no changes\n");
+
+    //
+    // source (now i64) -> narrow -> sum <- double <- narrow, with triple gone and the inputs of
+    // sum swapped, listed in a different order
+    //
+    let mut new_flow = Flow::new();
+    let sum = new_flow.add_new_task(SumTask::new(|a: &i32, b: &i32| a + b));
+    let source = new_flow.add_new_task(WideSourceTask::new(|| 1));
+    let narrow = new_flow.add_new_task(NarrowTask::new(|x: &i64| *x as i32));
+    let double = new_flow.add_new_task(ForwardTask::new(|x: &i32| x * 2));
+    new_flow.set_task_name(&source, "source");
+    new_flow.set_task_name(&narrow, "narrow");
+    new_flow.set_task_name(&double, "double");
+    new_flow.set_task_name(&sum, "sum");
+    new_flow.connect_output0_to_input0(&source, &narrow);
+    new_flow.connect_output0_to_input0(&narrow, &double);
+    new_flow.connect_output0_to_input1(&double, &sum);
+    new_flow.connect_output0_to_input0(&narrow, &sum);
+
+    let diff = old_flow.diff(&new_flow);
+    assert_eq!(
+        diff.get_changes()[0],
+        FlowChange::TaskRemoved {
+            task: String::from("triple")
+        }
+    );
+    assert_eq!(
+        diff.get_changes()[1],
+        FlowChange::TaskAdded {
+            task: String::from("narrow")
+        }
+    );
+    assert_eq!(
+        diff.to_string(),
+        "- task triple\n\
+         + task narrow\n\
+         - double.out0 -> sum.in0\n\
+         - source.out0 -> double.in0\n\
+         - source.out0 -> triple.in0\n\
+         - triple.out0 -> sum.in1\n\
+         + double.out0 -> sum.in1\n\
+         + narrow.out0 -> double.in0\n\
+         + narrow.out0 -> sum.in0\n\
+         + source.out0 -> narrow.in0\n\
+         ~ source.out0: i32 -> i64\n"
+    );
+}
+
+#[test]
+fn duplicate_and_unnamed_tasks() {
+    let mut old_flow = Flow::new();
+    let chunk_1 = old_flow.add_new_task(SourceTask::new(|| 1));
+    let chunk_2 = old_flow.add_new_task(SourceTask::new(|| 2));
+    let sum = old_flow.add_new_task(SumTask::new(|a: &i32, b: &i32| a + b));
+    old_flow.set_task_name(&chunk_1, "chunk");
+    old_flow.set_task_name(&chunk_2, "chunk");
+    old_flow.connect_output0_to_input0(&chunk_1, &sum);
+    old_flow.connect_output0_to_input1(&chunk_2, &sum);
+
+    let mut new_flow = old_flow.clone();
+    let chunk_3 = new_flow.add_new_task(SourceTask::new(|| 3));
+    new_flow.set_task_name(&chunk_3, "chunk");
+
+    assert_eq!(
+        old_flow.diff(&new_flow).get_changes(),
+        [FlowChange::TaskAdded {
+            task: String::from("chunk #3")
+        }]
+    );
+    assert_eq!(new_flow.diff(&old_flow).to_string(), "- task chunk #3\n");
+    assert_eq!(
+        format!("{}", old_flow.diff(&Flow::new())).lines().count(),
+        5
+    );
+    assert!(old_flow
+        .diff(&Flow::new())
+        .to_string()
+        .contains("- chunk #2.out0 -> task 2.in1"));
+}
+
+#[test]
+fn names_like_generated_labels() {
+    let mut flow = Flow::new();
+    let chunk_1 = flow.add_new_task(SourceTask::new(|| 1));
+    let chunk_2 = flow.add_new_task(SourceTask::new(|| 2));
+    let chunk_3 = flow.add_new_task(SourceTask::new(|| 3));
+    flow.add_new_task(SourceTask::new(|| 4));
+    let task_3 = flow.add_new_task(SourceTask::new(|| 5));
+    flow.set_task_name(&chunk_1, "chunk");
+    flow.set_task_name(&chunk_2, "chunk #2");
+    flow.set_task_name(&chunk_3, "chunk");
+    flow.set_task_name(&task_3, "task 3");
+
+    //
+    // the labels of the second chunk and of the unnamed task skip the names of other tasks
+    //
+    let changes = flow.diff(&Flow::new());
+    assert_eq!(
+        changes.get_changes(),
+        [
+            FlowChange::TaskRemoved {
+                task: String::from("chunk")
+            },
+            FlowChange::TaskRemoved {
+                task: String::from("chunk #2")
+            },
+            FlowChange::TaskRemoved {
+                task: String::from("chunk #3")
+            },
+            FlowChange::TaskRemoved {
+                task: String::from("task 3")
+            },
+            FlowChange::TaskRemoved {
+                task: String::from("task 3 #2")
+            },
+        ]
+    );
+}
@@ -0,0 +1,87 @@
+use rs_taskflow::algorithm;
+use rs_taskflow::algorithm::Partitioner;
+use rs_taskflow::execution::TaskStatus;
+use rs_taskflow::flow::Flow;
+use rs_taskflow::task::*;
+
+#[rs_taskflow_derive::derive_task((), (Vec<i32>,))]
+struct SourceTask;
+
+#[rs_taskflow_derive::derive_task((i32, i32), (i32, i32))]
+struct SwapTask;
+
+#[rs_taskflow_derive::derive_task((Vec<i32>,), (i32,))]
+struct SumTask;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn main() {
+    //
+    // declare system
+    //
+    let mut flow = Flow::new();
+
+    let source = flow.add_new_task(SourceTask::new(|| vec![3, 1, 2]));
+    let sorted = algorithm::sort::<i32>(&mut flow, Partitioner::new(2));
+    let sum = flow.add_new_task(SumTask::new(|x: &Vec<i32>| x.iter().sum()));
+    flow.set_task_name(&source, "source \"numbers\"");
+    flow.set_task_name(&sum, "sum");
+
+    flow.connect_output0_to_input0(&source, &sorted.entry);
+    flow.connect_output0_to_input0(&sorted.exit, &sum);
+
+    //
+    // check the exported graph
+    //
+    let dot = flow.to_dot();
+    assert!(dot.starts_with("digraph Flow {\n"));
+    assert!(dot.ends_with("}\n"));
+    assert!(dot.contains(&format!(
+        "  task{} [label=\"source \\\"numbers\\\" ({})\"];\n",
+        source.id(),
+        source.id()
+    )));
+    assert!(dot.contains("  subgraph cluster_0 {\n    label=\"sort\";\n"));
+    assert!(dot.contains(&format!(
+        "    task{} [label=\"scatter ({})\"];\n",
+        sorted.entry.id(),
+        sorted.entry.id()
+    )));
+    assert!(dot.contains(&format!(
+        "  task{} -> task{} [label=\"out0 -> in0\"];\n",
+        sorted.exit.id(),
+        sum.id()
+    )));
+    assert_eq!(dot.matches(" -> task").count(), 6);
+    assert_eq!(flow.get_task_cluster(sum.id()), None);
+    assert_eq!(flow.get_task_cluster(sorted.exit.id()), Some(0));
+    assert_eq!(flow.get_cluster_label(0), "sort");
+
+    //
+    // check the graph rendered from an execution
+    //
+    let flow_exec = flow.execute().await;
+    assert_eq!(*flow_exec.get_task_output0(&sum).unwrap(), 6);
+    assert_eq!(flow_exec.get_task_status(sum.id()), TaskStatus::Completed);
+
+    let dot = flow_exec.to_dot();
+    assert_eq!(
+        dot.matches("style=filled, fillcolor=\"palegreen\", tooltip=\"Completed\"")
+            .count(),
+        flow.get_num_tasks()
+    );
+}
+
+#[test]
+fn port_labels() {
+    let mut flow = Flow::new();
+
+    let swap_1 = flow.add_new_task(SwapTask::new(|a: &i32, b: &i32| (*b, *a)));
+    let swap_2 = flow.add_new_task(SwapTask::new(|a: &i32, b: &i32| (*b, *a)));
+    flow.connect_output0_to_input1(&swap_1, &swap_2);
+    flow.connect_output1_to_input0(&swap_1, &swap_2);
+
+    let dot = flow.to_dot();
+    assert!(dot.contains("  task0 [label=\"task 0\"];\n"));
+    assert!(dot.contains("  task0 -> task1 [label=\"out0 -> in1\"];\n"));
+    assert!(dot.contains("  task0 -> task1 [label=\"out1 -> in0\"];\n"));
+}
@@ -0,0 +1,135 @@
+use rs_taskflow::execution::{ExecutionEvent, TaskStatus};
+use rs_taskflow::flow::Flow;
+use rs_taskflow::task::*;
+
+#[rs_taskflow_derive::derive_task((), (i32,))]
+struct SourceTask;
+
+#[rs_taskflow_derive::derive_task((i32,), (i32,))]
+struct ForwardTask;
+
+#[rs_taskflow_derive::derive_task((i32, i32), (i32,))]
+struct SumTask;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn main() {
+    //
+    // declare system
+    //
+    let mut flow = Flow::new();
+
+    let source = flow.add_new_task(SourceTask::new(|| 1));
+    let good = flow.add_new_task(ForwardTask::new(|x: &i32| x + 1));
+    let bad = flow.add_new_task(ForwardTask::new(|_: &i32| -> i32 { panic!("bad input") }));
+    let sum = flow.add_new_task(SumTask::new(|a: &i32, b: &i32| a + b));
+    let after_sum = flow.add_new_task(ForwardTask::new(|x: &i32| x + 1));
+
+    flow.connect_output0_to_input0(&source, &good);
+    flow.connect_output0_to_input0(&source, &bad);
+    flow.connect_output0_to_input0(&good, &sum);
+    flow.connect_output0_to_input1(&bad, &sum);
+    flow.connect_output0_to_input0(&sum, &after_sum);
+
+    //
+    // starting running the system while collecting its events
+    //
+    let (flow_exec, mut events) = flow.execute_with_events();
+    let collector = tokio::spawn(async move {
+        let mut received = Vec::new();
+        while let Some(event) = events.recv().await {
+            received.push(event);
+        }
+        received
+    });
+    let flow_exec = flow_exec.await;
+    let received = collector.await.unwrap();
+
+    //
+    // check the results and the events of the system
+    //
+    assert_eq!(*flow_exec.get_task_output0(&good).unwrap(), 2);
+    assert_eq!(flow_exec.get_task_output0(&bad), None);
+    assert_eq!(flow_exec.get_task_output0(&after_sum), None);
+
+    assert_eq!(
+        flow_exec.get_task_status(source.id()),
+        TaskStatus::Completed
+    );
+    assert_eq!(flow_exec.get_task_status(good.id()), TaskStatus::Completed);
+    assert_eq!(flow_exec.get_task_status(bad.id()), TaskStatus::Failed);
+    assert_eq!(flow_exec.get_task_status(sum.id()), TaskStatus::Skipped);
+    assert_eq!(
+        flow_exec.get_task_status(after_sum.id()),
+        TaskStatus::Skipped
+    );
+
+    let position = |event: ExecutionEvent| received.iter().position(|e| *e == event).unwrap();
+    assert!(
+        position(ExecutionEvent::Ready(source.id()))
+            < position(ExecutionEvent::Started(source.id()))
+    );
+    assert!(
+        position(ExecutionEvent::Started(source.id()))
+            < position(ExecutionEvent::Finished(source.id()))
+    );
+    assert!(
+        position(ExecutionEvent::Finished(source.id()))
+            < position(ExecutionEvent::Started(bad.id()))
+    );
+    assert!(
+        position(ExecutionEvent::Failed(bad.id(), String::from("bad input")))
+            < position(ExecutionEvent::Skipped(sum.id()))
+    );
+    assert!(
+        position(ExecutionEvent::Skipped(sum.id()))
+            < position(ExecutionEvent::Skipped(after_sum.id()))
+    );
+    assert_eq!(received.len(), 3 * 3 + 2);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn cycle() {
+    //
+    // declare system
+    //
+    let mut flow = Flow::new();
+
+    let source = flow.add_new_task(SourceTask::new(|| 1));
+    let first = flow.add_new_task(SumTask::new(|a: &i32, b: &i32| a + b));
+    let second = flow.add_new_task(ForwardTask::new(|x: &i32| x + 1));
+    let after_cycle = flow.add_new_task(ForwardTask::new(|x: &i32| x + 1));
+    let unrelated = flow.add_new_task(ForwardTask::new(|x: &i32| x + 1));
+
+    flow.connect_output0_to_input0(&source, &first);
+    flow.connect_output0_to_input1(&second, &first);
+    flow.connect_output0_to_input0(&first, &second);
+    flow.connect_output0_to_input0(&second, &after_cycle);
+    flow.connect_output0_to_input0(&source, &unrelated);
+
+    //
+    // starting running the system, which reports the cycle instead of waiting on it
+    //
+    let (flow_exec, mut events) = flow.execute_with_events();
+    let flow_exec = flow_exec.await;
+
+    let mut failed = Vec::new();
+    while let Some(event) = events.recv().await {
+        if let ExecutionEvent::Failed(task_id, message) = event {
+            assert_eq!(message, "task is part of a dependency cycle");
+            failed.push(task_id);
+        }
+    }
+    failed.sort_unstable();
+
+    //
+    // check the results of the system
+    //
+    assert_eq!(failed, [first.id(), second.id()]);
+    assert_eq!(flow_exec.get_task_status(first.id()), TaskStatus::Failed);
+    assert_eq!(flow_exec.get_task_status(second.id()), TaskStatus::Failed);
+    assert_eq!(
+        flow_exec.get_task_status(after_cycle.id()),
+        TaskStatus::Skipped
+    );
+    assert_eq!(*flow_exec.get_task_output0(&unrelated).unwrap(), 2);
+}
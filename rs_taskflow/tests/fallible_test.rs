@@ -0,0 +1,141 @@
+use std::num::ParseIntError;
+
+use rs_taskflow::execution::{ExecutionEvent, TaskStatus};
+use rs_taskflow::flow::Flow;
+use rs_taskflow::task::*;
+use rs_taskflow_derive::task;
+
+#[rs_taskflow_derive::derive_task((), (String,))]
+struct TextTask;
+
+#[task]
+#[allow(clippy::ptr_arg)]
+fn parse_number(text: &String) -> Result<i64, ParseIntError> {
+    text.trim().parse()
+}
+
+#[task(error = std::io::Error)]
+fn halve(number: &i64) -> std::io::Result<i64> {
+    if number % 2 != 0 {
+        return Err(std::io::Error::other(format!("{} is odd", number)));
+    }
+    Ok(number / 2)
+}
+
+#[rs_taskflow_derive::derive_task((i64,), (i64, bool), error = String)]
+struct CheckedDoubleTask;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn main() {
+    //
+    // declare system
+    //
+    let mut flow = Flow::new();
+
+    let good_text = flow.add_new_task(TextTask::new(|| String::from(" 21 ")));
+    let bad_text = flow.add_new_task(TextTask::new(|| String::from("twenty")));
+    let good_parse = flow.add_new_task(ParseNumberTask::default());
+    let bad_parse = flow.add_new_task(ParseNumberTask::default());
+    let checked = |x: &i64| match x.checked_mul(2) {
+        Some(doubled) => Ok((doubled, doubled > 40)),
+        None => Err(format!("{} overflows", x)),
+    };
+    let good_double = flow.add_new_task(CheckedDoubleTask::new(checked));
+    let bad_double = flow.add_new_task(CheckedDoubleTask::new(checked));
+
+    flow.connect_output0_to_input0(&good_text, &good_parse);
+    flow.connect_output0_to_input0(&bad_text, &bad_parse);
+    flow.connect_output0_to_input0(&good_parse, &good_double);
+    flow.connect_output0_to_input0(&bad_parse, &bad_double);
+
+    //
+    // starting running the system while collecting its events
+    //
+    let (flow_exec, mut events) = flow.execute_with_events();
+    let collector = tokio::spawn(async move {
+        let mut received = Vec::new();
+        while let Some(event) = events.recv().await {
+            received.push(event);
+        }
+        received
+    });
+    let flow_exec = flow_exec.await;
+    let received = collector.await.unwrap();
+
+    //
+    // check the results of the system
+    //
+    assert_eq!(*flow_exec.get_task_output0(&good_parse).unwrap(), 21);
+    assert_eq!(*flow_exec.get_task_output0(&good_double).unwrap(), 42);
+    assert!(*flow_exec.get_task_output1(&good_double).unwrap());
+    assert_eq!(flow_exec.get_task_error(&good_parse), None);
+    assert_eq!(flow_exec.get_task_error(&good_double), None);
+
+    assert_eq!(flow_exec.get_task_output0(&bad_parse), None);
+    assert_eq!(
+        flow_exec.get_task_status(bad_parse.id()),
+        TaskStatus::Failed
+    );
+    assert_eq!(
+        flow_exec.get_task_status(bad_double.id()),
+        TaskStatus::Skipped
+    );
+    let error = flow_exec.get_task_error(&bad_parse).unwrap();
+    assert_eq!(error, &"twenty".parse::<i64>().unwrap_err());
+    assert!(received.contains(&ExecutionEvent::Failed(bad_parse.id(), error.to_string())));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn error_from_derive_task() {
+    let mut flow = Flow::new();
+
+    let text = flow.add_new_task(TextTask::new(|| i64::MAX.to_string()));
+    let parse = flow.add_new_task(ParseNumberTask::default());
+    let double = flow.add_new_task(CheckedDoubleTask::new(|x: &i64| match x.checked_mul(2) {
+        Some(doubled) => Ok((doubled, doubled > 40)),
+        None => Err(format!("{} overflows", x)),
+    }));
+
+    flow.connect_output0_to_input0(&text, &parse);
+    flow.connect_output0_to_input0(&parse, &double);
+
+    let flow_exec = flow.execute().await;
+
+    assert_eq!(flow_exec.get_task_status(parse.id()), TaskStatus::Completed);
+    assert_eq!(flow_exec.get_task_status(double.id()), TaskStatus::Failed);
+    assert_eq!(flow_exec.get_task_output0(&double), None);
+    assert_eq!(
+        flow_exec.get_task_error(&double).unwrap(),
+        "9223372036854775807 overflows"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn error_from_result_alias() {
+    let mut flow = Flow::new();
+
+    let good_text = flow.add_new_task(TextTask::new(|| String::from("42")));
+    let bad_text = flow.add_new_task(TextTask::new(|| String::from("21")));
+    let good_parse = flow.add_new_task(ParseNumberTask::default());
+    let bad_parse = flow.add_new_task(ParseNumberTask::default());
+    let good_halve = flow.add_new_task(HalveTask::default());
+    let bad_halve = flow.add_new_task(HalveTask::default());
+
+    flow.connect_output0_to_input0(&good_text, &good_parse);
+    flow.connect_output0_to_input0(&bad_text, &bad_parse);
+    flow.connect_output0_to_input0(&good_parse, &good_halve);
+    flow.connect_output0_to_input0(&bad_parse, &bad_halve);
+
+    let flow_exec = flow.execute().await;
+
+    assert_eq!(*flow_exec.get_task_output0(&good_halve).unwrap(), 21);
+    assert!(flow_exec.get_task_error(&good_halve).is_none());
+    assert_eq!(
+        flow_exec.get_task_status(bad_halve.id()),
+        TaskStatus::Failed
+    );
+    assert_eq!(
+        flow_exec.get_task_error(&bad_halve).unwrap().to_string(),
+        "21 is odd"
+    );
+}
@@ -16,11 +16,11 @@ async fn main() {
     //
     // create system components
     //
-    let input_task_handle = flow.add_new_task(ZeroInputTwoOutputTask::new(|| (42 as i32, 8 as u8)));
-    let task1_handle = flow.add_new_task(OneInputOneOutputTask::new(|x: &i32| x.clone()));
-    let task2_handle = flow.add_new_task(OneInputOneOutputTask::new(|x: &u8| x.clone()));
+    let input_task_handle = flow.add_new_task(ZeroInputTwoOutputTask::new(|| (42_i32, 8_u8)));
+    let task1_handle = flow.add_new_task(OneInputOneOutputTask::new(|x: &i32| *x));
+    let task2_handle = flow.add_new_task(OneInputOneOutputTask::new(|x: &u8| *x));
     let last_task_handle = flow.add_new_task(TwoInputOneOutputTask::new(|x: &i32, y: &u8| {
-        cast::<i32, i64>(x.clone()).unwrap() + cast::<u8, i64>(y.clone()).unwrap()
+        cast::<i32, i64>(*x).unwrap() + cast::<u8, i64>(*y).unwrap()
     }));
 
     //
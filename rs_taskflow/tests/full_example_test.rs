@@ -76,6 +76,7 @@ fn func_d(x: &i64) -> (Arc<Mutex<bool>>, Data) {
 #[rs_taskflow_derive::derive_task((String,), (String,))]
 struct TaskE;
 
+#[allow(clippy::ptr_arg)]
 fn func_e(x: &String) -> String {
     x.clone().add("\nFrom Task E")
 }
@@ -83,6 +84,7 @@ fn func_e(x: &String) -> String {
 #[rs_taskflow_derive::derive_task((Box<u8>, Arc<Mutex<bool>>), (u32,))]
 struct TaskF;
 
+#[allow(clippy::borrowed_box)]
 fn func_f(x: &Box<u8>, y: &Arc<Mutex<bool>>) -> u32 {
     let result = if *y.lock().unwrap() {
         x.bitxor(0b00000000)
@@ -101,7 +103,7 @@ fn func_g(x: &Data, y: &String) -> (u32, bool) {
     y.hash(&mut s);
     (
         s.finish() as u32,
-        y.eq_ignore_ascii_case(x.x) || x.y.load(Relaxed) % 2 == 0,
+        y.eq_ignore_ascii_case(x.x) || x.y.load(Relaxed).is_multiple_of(2),
     )
 }
 
@@ -0,0 +1,156 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use rs_taskflow::flow::Flow;
+use rs_taskflow::task::*;
+
+#[rs_taskflow_derive::derive_task((), (i32,))]
+struct ValueTask;
+
+#[rs_taskflow_derive::derive_task((#[join(collect)] i32, #[join(first_completed)] i32), (Vec<i32>, i32))]
+struct JoinTask;
+
+#[rs_taskflow_derive::derive_task((), (i32, i32))]
+struct PairTask;
+
+#[rs_taskflow_derive::derive_task((i32,), (i32,))]
+struct ReplaceTask;
+
+#[rs_taskflow_derive::derive_task((#[join(unique)] i32,), (i32,))]
+struct UniqueTask;
+
+#[rs_taskflow_derive::derive_task((i32, #[join(first_completed)] i32), (i32,))]
+struct GatedTask;
+
+static SLOW_TASK_DONE: AtomicBool = AtomicBool::new(false);
+static JOIN_TASK_SAW_SLOW_TASK_DONE: AtomicBool = AtomicBool::new(true);
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn main() {
+    //
+    // declare system
+    //
+    let mut flow = Flow::new();
+
+    let task_1 = flow.add_new_task(ValueTask::new(|| 1));
+    let task_2 = flow.add_new_task(ValueTask::new(|| 2));
+    let task_3 = flow.add_new_task(ValueTask::new(|| 3));
+    let slow_task = flow.add_new_task(ValueTask::new(|| {
+        thread::sleep(Duration::from_millis(500));
+        SLOW_TASK_DONE.store(true, Ordering::SeqCst);
+        100
+    }));
+    let fast_task = flow.add_new_task(ValueTask::new(|| 200));
+    let join_task = flow.add_new_task(JoinTask::new(|all: Vec<&i32>, first: &i32| {
+        JOIN_TASK_SAW_SLOW_TASK_DONE.store(SLOW_TASK_DONE.load(Ordering::SeqCst), Ordering::SeqCst);
+        (all.into_iter().copied().collect(), *first)
+    }));
+
+    flow.connect_output0_to_input0(&task_3, &join_task);
+    flow.connect_output0_to_input0(&task_1, &join_task);
+    flow.connect_output0_to_input0(&task_2, &join_task);
+    flow.connect_output0_to_input1(&slow_task, &join_task);
+    flow.connect_output0_to_input1(&fast_task, &join_task);
+    // connecting the same output again does not collect its value twice
+    flow.connect_output0_to_input0(&task_1, &join_task);
+
+    assert_eq!(flow.get_input_connections(join_task.id()).len(), 5);
+
+    //
+    // starting running the system
+    //
+    let flow_exec = flow.execute().await;
+
+    //
+    // check the results of the system
+    //
+    assert_eq!(
+        *flow_exec.get_task_output0(&join_task).unwrap(),
+        vec![3, 1, 2]
+    );
+    assert_eq!(*flow_exec.get_task_output1(&join_task).unwrap(), 200);
+    assert!(!JOIN_TASK_SAW_SLOW_TASK_DONE.load(Ordering::SeqCst));
+    assert!(SLOW_TASK_DONE.load(Ordering::SeqCst));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn first_completed_producer() {
+    //
+    // declare system
+    //
+    let mut flow = Flow::new();
+
+    // both producers have completed by the time the gate opens, but the later connected one
+    // completed first and is the one the task waited for
+    let slow_task = flow.add_new_task(ValueTask::new(|| {
+        thread::sleep(Duration::from_millis(100));
+        100
+    }));
+    let fast_task = flow.add_new_task(ValueTask::new(|| 200));
+    let gate_task = flow.add_new_task(ValueTask::new(|| {
+        thread::sleep(Duration::from_millis(400));
+        0
+    }));
+    let gated_task = flow.add_new_task(GatedTask::new(|gate: &i32, first: &i32| gate + first));
+
+    flow.connect_output0_to_input0(&gate_task, &gated_task);
+    flow.connect_output0_to_input1(&slow_task, &gated_task);
+    flow.connect_output0_to_input1(&fast_task, &gated_task);
+
+    //
+    // starting running the system
+    //
+    let flow_exec = flow.execute().await;
+
+    //
+    // check the results of the system
+    //
+    assert_eq!(*flow_exec.get_task_output0(&gated_task).unwrap(), 200);
+}
+
+#[test]
+#[should_panic(expected = "already has a producer")]
+fn duplicate_producer() {
+    let mut flow = Flow::new();
+
+    let task_1 = flow.add_new_task(ValueTask::new(|| 1));
+    let task_2 = flow.add_new_task(ValueTask::new(|| 2));
+    let unique_task = flow.add_new_task(UniqueTask::new(|x: &i32| *x));
+
+    flow.connect_output0_to_input0(&task_1, &unique_task);
+    flow.connect_output0_to_input0(&task_2, &unique_task);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn replaced_producer() {
+    let mut flow = Flow::new();
+
+    let task_1 = flow.add_new_task(ValueTask::new(|| 1));
+    let task_2 = flow.add_new_task(ValueTask::new(|| 2));
+    let replace_task = flow.add_new_task(ReplaceTask::new(|x: &i32| *x));
+
+    flow.connect_output0_to_input0(&task_1, &replace_task);
+    flow.connect_output0_to_input0(&task_2, &replace_task);
+
+    let connections = flow.get_input_connections(replace_task.id());
+    assert_eq!(connections.len(), 1);
+    assert_eq!(connections[0].source_task_id, task_2.id());
+
+    let flow_exec = flow.execute().await;
+    assert_eq!(*flow_exec.get_task_output0(&replace_task).unwrap(), 2);
+}
+
+#[test]
+#[should_panic(expected = "does not accept twice")]
+fn first_completed_same_task() {
+    let mut flow = Flow::new();
+
+    let gate_task = flow.add_new_task(ValueTask::new(|| 0));
+    let pair_task = flow.add_new_task(PairTask::new(|| (1, 2)));
+    let gated_task = flow.add_new_task(GatedTask::new(|gate: &i32, first: &i32| gate + first));
+
+    flow.connect_output0_to_input0(&gate_task, &gated_task);
+    flow.connect_output0_to_input1(&pair_task, &gated_task);
+    flow.connect_output1_to_input1(&pair_task, &gated_task);
+}
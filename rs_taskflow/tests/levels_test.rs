@@ -0,0 +1,48 @@
+use rs_taskflow::flow::Flow;
+use rs_taskflow::task::*;
+
+#[rs_taskflow_derive::derive_task((), (i32, i32))]
+struct SplitTask;
+
+#[rs_taskflow_derive::derive_task((i32,), (i32,))]
+struct IncTask;
+
+#[rs_taskflow_derive::derive_task((i32, i32), (i32,))]
+struct SumTask;
+
+#[test]
+fn main() {
+    let mut flow = Flow::new();
+
+    let split = flow.add_new_task(SplitTask::new(|| (1, 2)));
+    let sum = flow.add_new_task(SumTask::new(|a: &i32, b: &i32| a + b));
+    let inc_a = flow.add_new_task(IncTask::new(|x: &i32| x + 1));
+    let inc_b = flow.add_new_task(IncTask::new(|x: &i32| x + 1));
+
+    flow.connect_output0_to_input0(&split, &inc_a);
+    flow.connect_output1_to_input0(&split, &inc_b);
+    flow.connect_output0_to_input0(&inc_a, &sum);
+    flow.connect_output0_to_input1(&inc_b, &sum);
+
+    assert_eq!(
+        flow.get_levels().unwrap(),
+        vec![
+            vec![split.id()],
+            vec![inc_a.id(), inc_b.id()],
+            vec![sum.id()]
+        ]
+    );
+}
+
+#[test]
+fn cycle() {
+    let mut flow = Flow::new();
+
+    let inc_a = flow.add_new_task(IncTask::new(|x: &i32| x + 1));
+    let inc_b = flow.add_new_task(IncTask::new(|x: &i32| x + 1));
+
+    flow.connect_output0_to_input0(&inc_a, &inc_b);
+    flow.connect_output0_to_input0(&inc_b, &inc_a);
+
+    assert!(flow.get_levels().is_err());
+}
@@ -0,0 +1,102 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use rs_taskflow::flow::Flow;
+use rs_taskflow::map::{ElementError, MapTask};
+use rs_taskflow::task::*;
+
+#[rs_taskflow_derive::derive_task((), (Vec<u32>,))]
+struct InputTask;
+
+#[rs_taskflow_derive::derive_task((Vec<Result<u64, ElementError<String>>>,), (u64, usize))]
+struct SummaryTask;
+
+#[allow(clippy::ptr_arg)]
+fn summarize(results: &Vec<Result<u64, ElementError<String>>>) -> (u64, usize) {
+    let sum = results.iter().filter_map(|r| r.as_ref().ok()).sum();
+    let num_errors = results.iter().filter(|r| r.is_err()).count();
+    (sum, num_errors)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn main() {
+    let worker_threads = Arc::new(Mutex::new(HashSet::new()));
+
+    //
+    // declare system
+    //
+    let mut flow = Flow::new();
+
+    let worker_threads_ref = worker_threads.clone();
+    let input_task = flow.add_new_task(InputTask::new(|| (0..24).collect()));
+    let map_task = flow.add_new_task(MapTask::new(move |x: &u32| {
+        worker_threads_ref
+            .lock()
+            .unwrap()
+            .insert(thread::current().id());
+        thread::sleep(Duration::from_millis(((24 - x) % 4) as u64));
+        match x {
+            7 => Err(format!("{} is unlucky", x)),
+            13 => panic!("{} is very unlucky", x),
+            _ => Ok((*x as u64) * 10),
+        }
+    }));
+    let summary_task = flow.add_new_task(SummaryTask::new(summarize));
+
+    flow.connect_output0_to_input0(&input_task, &map_task);
+    flow.connect_output0_to_input0(&map_task, &summary_task);
+
+    //
+    // starting running the system
+    //
+    let flow_exec = flow.execute().await;
+
+    //
+    // check the results of the system
+    //
+    let results = flow_exec.get_task_output0(&map_task).unwrap();
+    assert_eq!(results.len(), 24);
+    for (i, result) in results.iter().enumerate() {
+        match i {
+            7 => assert_eq!(
+                *result,
+                Err(ElementError::Failed(String::from("7 is unlucky")))
+            ),
+            13 => assert_eq!(
+                *result,
+                Err(ElementError::Panicked(String::from("13 is very unlucky")))
+            ),
+            _ => assert_eq!(*result, Ok((i as u64) * 10)),
+        }
+    }
+
+    let expected_sum = (0..24u64).filter(|x| *x != 7 && *x != 13).sum::<u64>() * 10;
+    assert_eq!(
+        *flow_exec.get_task_output0(&summary_task).unwrap(),
+        expected_sum
+    );
+    assert_eq!(*flow_exec.get_task_output1(&summary_task).unwrap(), 2);
+
+    assert!(worker_threads.lock().unwrap().len() > 1);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn single_threaded() {
+    let mut flow = Flow::new();
+
+    let input_task = flow.add_new_task(InputTask::new(|| vec![1, 2, 3]));
+    let map_task = flow.add_new_task(MapTask::new(|x: &u32| match x {
+        2 => Err(()),
+        _ => Ok(*x * 2),
+    }));
+    flow.connect_output0_to_input0(&input_task, &map_task);
+
+    let flow_exec = flow.execute().await;
+
+    assert_eq!(
+        *flow_exec.get_task_output0(&map_task).unwrap(),
+        vec![Ok(2), Err(ElementError::Failed(())), Ok(6)]
+    );
+}
@@ -40,10 +40,10 @@ async fn main() {
     //
     let input_task_handle =
         flow.add_new_task(ZeroInputTwoOutputTask::new(|| OUTPUTTER.get_output()));
-    let task1_handle = flow.add_new_task(OneInputOneOutputTask::new(|x: &i32| x.clone()));
-    let task2_handle = flow.add_new_task(OneInputOneOutputTask::new(|x: &u8| x.clone()));
+    let task1_handle = flow.add_new_task(OneInputOneOutputTask::new(|x: &i32| *x));
+    let task2_handle = flow.add_new_task(OneInputOneOutputTask::new(|x: &u8| *x));
     let last_task_handle = flow.add_new_task(TwoInputOneOutputTask::new(|x: &i32, y: &u8| {
-        cast::<i32, i64>(x.clone()).unwrap() + cast::<u8, i64>(y.clone()).unwrap()
+        cast::<i32, i64>(*x).unwrap() + cast::<u8, i64>(*y).unwrap()
     }));
 
     if cfg!(debug_assertions) {
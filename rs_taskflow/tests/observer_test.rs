@@ -0,0 +1,106 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::thread::ThreadId;
+use std::time::Instant;
+
+use rs_taskflow::flow::Flow;
+use rs_taskflow::observer::{Observer, TaskInfo};
+use rs_taskflow::task::*;
+
+#[rs_taskflow_derive::derive_task((), (i32,))]
+struct SourceTask;
+
+#[rs_taskflow_derive::derive_task((i32,), (i32,))]
+struct ForwardTask;
+
+#[derive(Debug, PartialEq)]
+enum Event {
+    ExecutionStart(usize),
+    ExecutionEnd,
+    Entry(usize, String),
+    Exit(usize, String),
+}
+
+#[derive(Default)]
+struct RecordingObserver {
+    events: Mutex<Vec<(Event, Instant)>>,
+    workers: Mutex<HashSet<ThreadId>>,
+}
+
+impl Observer for RecordingObserver {
+    fn on_execution_start(&self, num_tasks: usize, time: Instant) {
+        self.events
+            .lock()
+            .unwrap()
+            .push((Event::ExecutionStart(num_tasks), time));
+    }
+
+    fn on_execution_end(&self, time: Instant) {
+        self.events
+            .lock()
+            .unwrap()
+            .push((Event::ExecutionEnd, time));
+    }
+
+    fn on_entry(&self, task: &TaskInfo, time: Instant) {
+        self.workers.lock().unwrap().insert(task.worker);
+        self.events.lock().unwrap().push((
+            Event::Entry(task.task_id, String::from(task.task_name)),
+            time,
+        ));
+    }
+
+    fn on_exit(&self, task: &TaskInfo, start_time: Instant, end_time: Instant) {
+        assert!(start_time <= end_time);
+        self.events.lock().unwrap().push((
+            Event::Exit(task.task_id, String::from(task.task_name)),
+            end_time,
+        ));
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn main() {
+    let observer = Arc::new(RecordingObserver::default());
+
+    //
+    // declare system
+    //
+    let mut flow = Flow::new();
+    flow.add_observer(observer.clone());
+
+    let source = flow.add_new_task(SourceTask::new(|| 1));
+    let left = flow.add_new_task(ForwardTask::new(|x: &i32| x + 1));
+    let right = flow.add_new_task(ForwardTask::new(|x: &i32| x + 2));
+    flow.set_task_name(&source, "source");
+    flow.set_task_name(&left, "left");
+
+    flow.connect_output0_to_input0(&source, &left);
+    flow.connect_output0_to_input0(&source, &right);
+
+    //
+    // starting running the system
+    //
+    let flow_exec = flow.execute().await;
+    assert_eq!(*flow_exec.get_task_output0(&right).unwrap(), 3);
+
+    //
+    // check what the observer saw
+    //
+    let events = observer.events.lock().unwrap();
+    assert_eq!(events.len(), 2 + 2 * 3);
+    assert_eq!(events.first().unwrap().0, Event::ExecutionStart(3));
+    assert_eq!(events.last().unwrap().0, Event::ExecutionEnd);
+
+    let position = |event: Event| events.iter().position(|(e, _)| *e == event).unwrap();
+    let source_exit = position(Event::Exit(source.id(), String::from("source")));
+    assert!(position(Event::Entry(source.id(), String::from("source"))) < source_exit);
+    assert!(source_exit < position(Event::Entry(left.id(), String::from("left"))));
+    assert!(source_exit < position(Event::Entry(right.id(), String::new())));
+    assert!(
+        position(Event::Entry(left.id(), String::from("left")))
+            < position(Event::Exit(left.id(), String::from("left")))
+    );
+
+    assert!(!observer.workers.lock().unwrap().is_empty());
+}
@@ -0,0 +1,49 @@
+use rs_taskflow::flow::Flow;
+use rs_taskflow::task::*;
+
+#[rs_taskflow_derive::derive_task((), (i64,))]
+struct ValueTask;
+
+#[rs_taskflow_derive::derive_task((i64, #[optional] i64, i64 = 10), (i64,))]
+struct ScaleTask;
+
+fn scale(x: &i64, offset: Option<&i64>, factor: &i64) -> i64 {
+    x * factor + offset.copied().unwrap_or(0)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn main() {
+    //
+    // declare system
+    //
+    let mut flow = Flow::new();
+
+    let value_task = flow.add_new_task(ValueTask::new(|| 7));
+    let offset_task = flow.add_new_task(ValueTask::new(|| 3));
+    let factor_task = flow.add_new_task(ValueTask::new(|| 2));
+
+    let defaults_task = flow.add_new_task(ScaleTask::new(scale));
+    let offset_only_task = flow.add_new_task(ScaleTask::new(scale));
+    let all_inputs_task = flow.add_new_task(ScaleTask::new(scale));
+
+    flow.connect_output0_to_input0(&value_task, &defaults_task);
+
+    flow.connect_output0_to_input0(&value_task, &offset_only_task);
+    flow.connect_output0_to_input1(&offset_task, &offset_only_task);
+
+    flow.connect_output0_to_input0(&value_task, &all_inputs_task);
+    flow.connect_output0_to_input1(&offset_task, &all_inputs_task);
+    flow.connect_output0_to_input2(&factor_task, &all_inputs_task);
+
+    //
+    // starting running the system
+    //
+    let flow_exec = flow.execute().await;
+
+    //
+    // check the results of the system
+    //
+    assert_eq!(*flow_exec.get_task_output0(&defaults_task).unwrap(), 70);
+    assert_eq!(*flow_exec.get_task_output0(&offset_only_task).unwrap(), 73);
+    assert_eq!(*flow_exec.get_task_output0(&all_inputs_task).unwrap(), 17);
+}
@@ -0,0 +1,34 @@
+use petgraph::graph::NodeIndex;
+
+use rs_taskflow::flow::Flow;
+use rs_taskflow::task::*;
+
+#[rs_taskflow_derive::derive_task((), (i32, i32))]
+struct SplitTask;
+
+#[rs_taskflow_derive::derive_task((i32, i32), (i32,))]
+struct SumTask;
+
+#[test]
+fn main() {
+    let mut flow = Flow::new();
+
+    let split = flow.add_new_task(SplitTask::new(|| (1, 2)));
+    let sum = flow.add_new_task(SumTask::new(|a: &i32, b: &i32| a + b));
+    flow.set_task_name(&split, "split");
+    flow.connect_output0_to_input0(&split, &sum);
+    flow.connect_output1_to_input1(&split, &sum);
+
+    let graph = flow.to_petgraph();
+    assert_eq!(graph.node_count(), 2);
+    assert_eq!(graph[NodeIndex::new(split.id())], "split");
+    assert_eq!(graph[NodeIndex::new(sum.id())], "");
+
+    let mut ports: Vec<(usize, usize)> = graph
+        .edges_connecting(NodeIndex::new(split.id()), NodeIndex::new(sum.id()))
+        .map(|edge| (edge.weight().source_port, edge.weight().target_port))
+        .collect();
+    ports.sort_unstable();
+    assert_eq!(ports, [(0, 0), (1, 1)]);
+    assert!(!petgraph::algo::is_cyclic_directed(&graph));
+}
@@ -0,0 +1,125 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use rs_taskflow::execution::TaskStatus;
+use rs_taskflow::flow::Flow;
+use rs_taskflow::pipeline::{PipeType, Pipeline};
+use rs_taskflow::task::*;
+
+#[rs_taskflow_derive::derive_task((), (usize,))]
+struct NumItemsTask;
+
+#[rs_taskflow_derive::derive_task((Vec<i64>,), (i64,))]
+struct SumTask;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn main() {
+    const NUM_ITEMS: usize = 40;
+    const NUM_TOKENS: usize = 4;
+
+    let serial_order = Arc::new(Mutex::new(Vec::<usize>::new()));
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let max_in_flight = Arc::new(AtomicUsize::new(0));
+
+    //
+    // declare system
+    //
+    let mut flow = Flow::new();
+
+    let serial_order_ref = serial_order.clone();
+    let in_flight_start = in_flight.clone();
+    let in_flight_end = in_flight.clone();
+    let max_in_flight_ref = max_in_flight.clone();
+    let pipeline = Pipeline::new(NUM_TOKENS, |n: &usize| 0..*n)
+        .add_stage(PipeType::Parallel, move |x: usize| {
+            let curr = in_flight_start.fetch_add(1, Ordering::SeqCst) + 1;
+            max_in_flight_ref.fetch_max(curr, Ordering::SeqCst);
+            // make later items finish first so that ordering is actually exercised
+            thread::sleep(Duration::from_millis(((NUM_ITEMS - x) % 5) as u64));
+            x
+        })
+        .add_stage(PipeType::Serial, move |x: usize| {
+            serial_order_ref.lock().unwrap().push(x);
+            (x * 2) as i64
+        })
+        .add_stage(PipeType::Parallel, move |x: i64| {
+            in_flight_end.fetch_sub(1, Ordering::SeqCst);
+            x + 1
+        });
+    assert_eq!(pipeline.get_num_tokens(), NUM_TOKENS);
+    assert_eq!(pipeline.get_num_stages(), 3);
+
+    let num_items_task = flow.add_new_task(NumItemsTask::new(|| NUM_ITEMS));
+    let pipeline_task = flow.add_new_task(pipeline);
+    let sum_task = flow.add_new_task(SumTask::new(|x: &Vec<i64>| x.iter().sum()));
+
+    flow.connect_output0_to_input0(&num_items_task, &pipeline_task);
+    flow.connect_output0_to_input0(&pipeline_task, &sum_task);
+
+    //
+    // starting running the system
+    //
+    let flow_exec = flow.execute().await;
+
+    //
+    // check the results of the system
+    //
+    let expected: Vec<i64> = (0..NUM_ITEMS).map(|x| (x * 2 + 1) as i64).collect();
+    assert_eq!(
+        *flow_exec.get_task_output0(&pipeline_task).unwrap(),
+        expected
+    );
+    assert_eq!(
+        *flow_exec.get_task_output0(&sum_task).unwrap(),
+        expected.iter().sum::<i64>()
+    );
+
+    assert_eq!(
+        *serial_order.lock().unwrap(),
+        (0..NUM_ITEMS).collect::<Vec<usize>>()
+    );
+    assert!(max_in_flight.load(Ordering::SeqCst) <= NUM_TOKENS);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn serial_stage_panic() {
+    //
+    // declare system
+    //
+    let mut flow = Flow::new();
+
+    let pipeline = Pipeline::new(4, |n: &usize| 0..*n)
+        .add_stage(PipeType::Parallel, |x: usize| x)
+        .add_stage(PipeType::Serial, |x: usize| {
+            if x == 5 {
+                panic!("bad item {}", x);
+            }
+            x as i64
+        });
+
+    let num_items_task = flow.add_new_task(NumItemsTask::new(|| 20));
+    let pipeline_task = flow.add_new_task(pipeline);
+    let sum_task = flow.add_new_task(SumTask::new(|x: &Vec<i64>| x.iter().sum()));
+
+    flow.connect_output0_to_input0(&num_items_task, &pipeline_task);
+    flow.connect_output0_to_input0(&pipeline_task, &sum_task);
+
+    //
+    // starting running the system, which must not hang on the tokens behind the panic
+    //
+    let flow_exec = flow.execute().await;
+
+    //
+    // check the results of the system
+    //
+    assert_eq!(
+        flow_exec.get_task_status(pipeline_task.id()),
+        TaskStatus::Failed
+    );
+    assert_eq!(
+        flow_exec.get_task_status(sum_task.id()),
+        TaskStatus::Skipped
+    );
+}
@@ -20,7 +20,7 @@ fn dummy2(_task: &dyn ExecutableTask) -> Option<&Option<bool>> {
 #[test]
 fn works() {
     let mut test_task =
-        TestTask::new(|_x: &i32, _y: &u8, _z: &Option<bool>| (54 as i64, String::from("asdf")));
+        TestTask::new(|_x: &i32, _y: &u8, _z: &Option<bool>| (54_i64, String::from("asdf")));
 
     test_task.set_input_0(TaskInputHandle::new(0, dummy0));
     test_task.set_input_1(TaskInputHandle::new(0, dummy1));
@@ -30,3 +30,93 @@ fn works() {
     // let output0 = TestTask::get_output_0(&test_task);
     // let output1 = TestTask::get_output_1(&test_task);
 }
+
+#[derive_task(
+    (base: i64, #[optional] offset: i64, scale: i64 = 10, std::primitive::u8),
+    (i64, scaled: i64)
+)]
+struct NamedPortsTask;
+
+#[derive_task((), (value: i64,))]
+struct NamedSourceTask;
+
+#[derive_task((), (u8,))]
+struct ByteSourceTask;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn named_ports() {
+    for connect_offset in [true, false] {
+        let mut flow = Flow::new();
+
+        let source = flow.add_new_task(NamedSourceTask::new(|| 4));
+        let byte = flow.add_new_task(ByteSourceTask::new(|| 1));
+        let task = flow.add_new_task(NamedPortsTask::new(
+            |base: &i64, offset: Option<&i64>, scale: &i64, unnamed: &u8| {
+                let offset = *offset.unwrap_or(&0);
+                (base + offset + *unnamed as i64, (base + offset) * scale)
+            },
+        ));
+
+        let inputs = task.inputs();
+        assert_eq!(inputs.base.get_task_id(), task.id());
+        assert_eq!(inputs.scale.get_port(), 2);
+        assert_eq!(task.outputs().scaled.get_port(), 1);
+
+        // the scale is left to its default, and the offset is optional
+        flow.connect_ports(source.outputs().value, task.inputs().base);
+        if connect_offset {
+            flow.connect_ports(source.outputs().value, task.inputs().offset);
+        }
+        flow.connect_output0_to_input3(&byte, &task);
+        assert_eq!(
+            flow.get_input_connections(task.id()).len(),
+            if connect_offset { 3 } else { 2 }
+        );
+
+        let flow_exec = flow.execute().await;
+        if connect_offset {
+            assert_eq!(*flow_exec.get_task_output0(&task).unwrap(), 9);
+            assert_eq!(*flow_exec.get_task_output1(&task).unwrap(), 80);
+        } else {
+            assert_eq!(*flow_exec.get_task_output0(&task).unwrap(), 5);
+            assert_eq!(*flow_exec.get_task_output1(&task).unwrap(), 40);
+        }
+    }
+}
+
+/// Adds a configured offset to its input
+#[derive_task((value: T,), (T,))]
+#[derive(Clone, Debug)]
+struct OffsetTask<T>
+where
+    T: Copy + Send + Sync + 'static,
+{
+    offset: T,
+    label: &'static str,
+}
+
+fn add_offset(fields: OffsetTaskFields<i64>, value: &i64) -> i64 {
+    value + fields.offset
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn user_struct() {
+    for offset in [2, 10] {
+        let mut flow = Flow::new();
+
+        let source = flow.add_new_task(NamedSourceTask::new(|| 4));
+        let offset_task = OffsetTask::new(
+            add_offset as fn(OffsetTaskFields<i64>, &i64) -> i64,
+            offset,
+            "offset",
+        );
+        assert_eq!(offset_task.label, "offset");
+        assert!(format!("{:?}", offset_task).contains(&format!("offset: {}", offset)));
+
+        let task = flow.add_new_task(offset_task);
+        flow.connect_ports(source.outputs().value, task.inputs().value);
+
+        let flow_exec = flow.execute().await;
+        assert_eq!(*flow_exec.get_task_output0(&task).unwrap(), 4 + offset);
+    }
+}
@@ -83,8 +83,10 @@ impl FakeFlow {
     fn connect<I, O, A: TaskOutput0<O>, B: TaskInput0<I>, T>(
         &mut self,
         _task1_handle: &TaskHandle<A>,
+        _task1_port: usize,
         _task1_output: fn(&dyn ExecutableTask) -> Option<&T>,
         _task2_handle: &TaskHandle<B>,
+        _task2_port: usize,
         _task2_input: fn(&mut B, TaskInputHandle<T>),
     ) {
         unimplemented!()
@@ -0,0 +1,82 @@
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use rs_taskflow::flow::Flow;
+use rs_taskflow::profiler::Profiler;
+use rs_taskflow::task::*;
+
+#[rs_taskflow_derive::derive_task((), (i32,))]
+struct SourceTask;
+
+#[rs_taskflow_derive::derive_task((i32,), (i32,))]
+struct SleepTask;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn main() {
+    let profiler = Arc::new(Profiler::new());
+
+    //
+    // declare system
+    //
+    let mut flow = Flow::new();
+    flow.add_observer(profiler.clone());
+
+    let source = flow.add_new_task(SourceTask::new(|| 1));
+    let left = flow.add_new_task(SleepTask::new(|x: &i32| {
+        thread::sleep(Duration::from_millis(20));
+        x + 1
+    }));
+    let right = flow.add_new_task(SleepTask::new(|x: &i32| {
+        thread::sleep(Duration::from_millis(20));
+        x + 2
+    }));
+    flow.set_task_name(&source, "source");
+    flow.set_task_name(&left, "left \"branch\"");
+
+    flow.connect_output0_to_input0(&source, &left);
+    flow.connect_output0_to_input0(&source, &right);
+
+    //
+    // starting running the system
+    //
+    flow.execute().await;
+    assert_eq!(profiler.get_num_recorded_tasks(), 3);
+    assert!(profiler.get_num_workers() >= 1);
+
+    //
+    // check the written trace
+    //
+    let mut trace = Vec::new();
+    profiler.write_chrome_trace(&mut trace).unwrap();
+    let trace: serde_json::Value = serde_json::from_slice(&trace).unwrap();
+
+    let events = trace["traceEvents"].as_array().unwrap();
+    let task_events: Vec<_> = events.iter().filter(|e| e["ph"] == "X").collect();
+    assert_eq!(task_events.len(), 3);
+    assert_eq!(
+        events.iter().filter(|e| e["ph"] == "M").count(),
+        profiler.get_num_workers()
+    );
+
+    let task_event = |task_id: usize| {
+        *task_events
+            .iter()
+            .find(|e| e["args"]["task_id"] == task_id)
+            .unwrap()
+    };
+    assert_eq!(task_event(source.id())["name"], "source");
+    assert_eq!(task_event(left.id())["name"], "left \"branch\"");
+    assert_eq!(
+        task_event(right.id())["name"],
+        format!("task {}", right.id())
+    );
+    assert!(task_event(left.id())["dur"].as_u64().unwrap() >= 20_000);
+
+    let source_end = task_event(source.id())["ts"].as_u64().unwrap()
+        + task_event(source.id())["dur"].as_u64().unwrap();
+    assert!(source_end <= task_event(right.id())["ts"].as_u64().unwrap());
+
+    profiler.clear();
+    assert_eq!(profiler.get_num_recorded_tasks(), 0);
+}
@@ -0,0 +1,262 @@
+use serde::{Deserialize, Serialize};
+
+use rs_taskflow::flow::{Connection, Flow};
+use rs_taskflow::registry::{FlowDescription, RegistryError, TaskRegistry};
+use rs_taskflow::task::*;
+
+// a hand-written task whose whole state can be serialized
+#[derive(Clone, Serialize, Deserialize)]
+struct ConstTask {
+    value: i64,
+}
+
+impl ExecutableTask for ConstTask {
+    fn exec(&mut self, _flow: &Flow) {}
+}
+
+impl TaskOutput0<i64> for ConstTask {
+    fn get_output_0(task: &dyn ExecutableTask) -> Option<&i64> {
+        Some(
+            &(task as &dyn std::any::Any)
+                .downcast_ref::<ConstTask>()?
+                .value,
+        )
+    }
+}
+
+impl ErasedPorts for ConstTask {
+    fn get_erased_output(task_id: usize, port: usize) -> Option<ErasedOutput> {
+        match port {
+            0 => Some(ErasedOutput::new(task_id, port, Self::get_output_0)),
+            _ => None,
+        }
+    }
+
+    fn set_erased_input(&mut self, _port: usize, _output: &ErasedOutput) -> bool {
+        false
+    }
+}
+
+#[rs_taskflow_derive::derive_task((i64,), (i64,))]
+struct DoubleTask;
+
+#[rs_taskflow_derive::derive_task((i64, #[join(first_completed)] i64), (i64,))]
+struct SumTask;
+
+fn double(x: &i64) -> i64 {
+    2 * x
+}
+
+fn sum(a: &i64, b: &i64) -> i64 {
+    a + b
+}
+
+type DoubleFunc = fn(&i64) -> i64;
+type SumFunc = fn(&i64, &i64) -> i64;
+
+fn build_registry() -> TaskRegistry {
+    let mut registry = TaskRegistry::new();
+    registry.register::<ConstTask>("const");
+    registry.register_with(
+        "double",
+        |_: &DoubleTask<DoubleFunc>| Ok(serde_json::Value::Null),
+        |_| Ok(DoubleTask::new(double as DoubleFunc)),
+    );
+    registry.register_with(
+        "sum",
+        |_: &SumTask<SumFunc>| Ok(serde_json::Value::Null),
+        |_| Ok(SumTask::new(sum as SumFunc)),
+    );
+    registry
+}
+
+fn build_flow() -> Flow {
+    let mut flow = Flow::new();
+
+    let const_1 = flow.add_new_task(ConstTask { value: 3 });
+    let const_2 = flow.add_new_task(ConstTask { value: 4 });
+    let double_1 = flow.add_new_task(DoubleTask::new(double as DoubleFunc));
+    let sum = flow.add_new_task(SumTask::new(sum as SumFunc));
+    flow.set_task_name(&const_1, "three");
+    let cluster = flow.add_cluster("doubling");
+    flow.set_task_cluster(&double_1, cluster);
+
+    flow.connect_output0_to_input0(&const_1, &double_1);
+    flow.connect_output0_to_input0(&double_1, &sum);
+    flow.connect_output0_to_input1(&const_1, &sum);
+    flow.connect_output0_to_input1(&const_2, &sum);
+
+    flow
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn main() {
+    let registry = build_registry();
+    let flow = build_flow();
+
+    //
+    // describe the flow and write it out
+    //
+    let description = flow.to_description(&registry).unwrap();
+    let graph = &description.graph;
+    assert_eq!(graph.get_num_nodes(), 4);
+    assert_eq!(graph.get_node(0).get_value().name, "three");
+    assert_eq!(graph.get_node(0).get_value().kind, "const");
+    assert_eq!(
+        graph.get_node(1).get_value().payload,
+        serde_json::json!({"value": 4})
+    );
+    assert_eq!(graph.get_node(2).get_value().cluster, Some(0));
+    assert_eq!(graph.get_any_of_dependencies(3).len(), 1);
+    assert_eq!(description.clusters, ["doubling"]);
+    assert_eq!(description.connections.len(), 4);
+
+    let json = serde_json::to_string_pretty(&description).unwrap();
+    assert!(json.contains("\"kind\": \"double\""));
+    assert!(json.contains("\"any_of_group\": 1"));
+
+    //
+    // read it back and rebuild the tasks
+    //
+    let read: FlowDescription = serde_json::from_str(&json).unwrap();
+    assert!(read == description);
+    assert!(read == build_flow().to_description(&registry).unwrap());
+
+    let mut rebuilt = read.to_flow(&registry).unwrap();
+    assert!(rebuilt.to_description(&registry).unwrap() == description);
+    assert_eq!(rebuilt.get_num_tasks(), 4);
+    assert_eq!(rebuilt.get_task_name(0), "three");
+    assert_eq!(rebuilt.get_task_cluster(2), Some(0));
+    assert_eq!(
+        rebuilt.get_levels().unwrap(),
+        [vec![0, 1], vec![2], vec![3]]
+    );
+    assert!(rebuilt.diff(&flow).is_empty());
+
+    //
+    // run the rebuilt flow on a single worker, which lets the first constant complete first
+    //
+    rebuilt.set_single_worker(true);
+    let flow_exec = rebuilt.execute().await;
+    let sum = rebuilt.get_task_handle::<SumTask<SumFunc>>(3).unwrap();
+    assert!(rebuilt.get_task_handle::<ConstTask>(3).is_none());
+    assert_eq!(*flow_exec.get_task_output0(&sum).unwrap(), 9);
+}
+
+#[test]
+fn registry_errors() {
+    let flow = build_flow();
+
+    match flow.to_description(&TaskRegistry::new()) {
+        Err(RegistryError::UnregisteredTask { task_id: 0 }) => {}
+        _ => panic!("expected an unregistered task"),
+    }
+
+    let registry = build_registry();
+    let description = flow.to_description(&registry).unwrap();
+
+    let invalid_payload = FlowDescription {
+        graph: description
+            .graph
+            .try_map(|task_id, task| {
+                let mut task = task.clone();
+                if task_id == 1 {
+                    task.payload = serde_json::json!({"value": "four"});
+                }
+                Ok::<_, ()>(task)
+            })
+            .unwrap(),
+        ..description.clone()
+    };
+    match invalid_payload.to_flow(&registry) {
+        Err(RegistryError::InvalidPayload { task_id: 1, .. }) => {}
+        _ => panic!("expected an invalid payload"),
+    }
+
+    let unknown_kind = FlowDescription {
+        graph: description
+            .graph
+            .try_map(|task_id, task| {
+                let mut task = task.clone();
+                if task_id == 2 {
+                    task.kind = String::from("triple");
+                }
+                Ok::<_, ()>(task)
+            })
+            .unwrap(),
+        ..description.clone()
+    };
+    match unknown_kind.to_flow(&registry) {
+        Err(RegistryError::UnknownKind { task_id: 2, kind }) => assert_eq!(kind, "triple"),
+        _ => panic!("expected an unknown kind"),
+    }
+
+    // the second constant also feeding the first input of the sum, which only takes the double
+    let mut connections = description.connections.clone();
+    connections.push(Connection {
+        source_task_id: 1,
+        source_port: 0,
+        target_task_id: 3,
+        target_port: 0,
+    });
+    let second_producer = FlowDescription {
+        connections,
+        ..description.clone()
+    };
+    match second_producer.to_flow(&registry) {
+        Err(RegistryError::InvalidConnection { connection }) => {
+            assert_eq!(connection, second_producer.connections[4])
+        }
+        _ => panic!("expected an invalid connection"),
+    }
+
+    // an edge that no connection backs
+    let mut graph = description.graph.clone();
+    graph.connect(1, 2);
+    let stale_graph = FlowDescription {
+        graph,
+        ..description.clone()
+    };
+    match stale_graph.to_flow(&registry) {
+        Err(RegistryError::MismatchedEdges { task_id: 2 }) => {}
+        _ => panic!("expected mismatched edges"),
+    }
+
+    let mut connections = description.connections.clone();
+    connections[0].source_port = 1;
+    let missing_port = FlowDescription {
+        connections,
+        ..description
+    };
+    match missing_port.to_flow(&registry) {
+        Err(RegistryError::InvalidConnection { connection }) => {
+            assert_eq!(connection.source_port, 1)
+        }
+        _ => panic!("expected an invalid connection"),
+    }
+
+    let mut registry = TaskRegistry::new();
+    registry.register_with(
+        "const",
+        |_: &ConstTask| Err(serde::ser::Error::custom("not today")),
+        |_| Ok(ConstTask { value: 0 }),
+    );
+    let mut flow = Flow::new();
+    flow.add_new_task(ConstTask { value: 1 });
+    match flow.to_description(&registry) {
+        Err(RegistryError::UnserializableTask {
+            task_id: 0, kind, ..
+        }) => {
+            assert_eq!(kind, "const")
+        }
+        _ => panic!("expected an unserializable task"),
+    }
+}
+
+#[test]
+#[should_panic(expected = "is already registered as kind \"const\"")]
+fn duplicate_type() {
+    let mut registry = TaskRegistry::new();
+    registry.register::<ConstTask>("const");
+    registry.register::<ConstTask>("constant");
+}
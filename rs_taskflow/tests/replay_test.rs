@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rs_taskflow::execution::TaskStatus;
+use rs_taskflow::flow::{Flow, TaskHandle};
+use rs_taskflow::replay::{Recording, ReplayError};
+use rs_taskflow::task::*;
+
+#[rs_taskflow_derive::derive_task((), (Vec<i64>,))]
+struct SourceTask;
+
+#[rs_taskflow_derive::derive_task((Vec<i64>,), (i64, i64))]
+struct StatsTask;
+
+#[rs_taskflow_derive::derive_task((i64, i64), (i64,))]
+struct RangeTask;
+
+#[rs_taskflow_derive::derive_task((), (BTreeMap<(i64, i64), i64>,))]
+struct PairsTask;
+
+static SOURCE_RUNS: AtomicUsize = AtomicUsize::new(0);
+static STATS_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+fn source() -> Vec<i64> {
+    SOURCE_RUNS.fetch_add(1, Ordering::SeqCst);
+    vec![4, -2, 9, 1]
+}
+
+#[allow(clippy::ptr_arg)]
+fn stats(x: &Vec<i64>) -> (i64, i64) {
+    STATS_RUNS.fetch_add(1, Ordering::SeqCst);
+    (*x.iter().min().unwrap(), *x.iter().max().unwrap())
+}
+
+fn range(min: &i64, max: &i64) -> i64 {
+    max - min
+}
+
+type SourceFunc = fn() -> Vec<i64>;
+type StatsFunc = fn(&Vec<i64>) -> (i64, i64);
+type RangeFunc = fn(&i64, &i64) -> i64;
+
+struct TestFlow {
+    flow: Flow,
+    source: TaskHandle<SourceTask<SourceFunc>>,
+    stats: TaskHandle<StatsTask<StatsFunc>>,
+    range: TaskHandle<RangeTask<RangeFunc>>,
+}
+
+fn build_flow() -> TestFlow {
+    let mut flow = Flow::new();
+
+    let source = flow.add_new_task(SourceTask::new(source as SourceFunc));
+    let stats = flow.add_new_task(StatsTask::new(stats as StatsFunc));
+    let range = flow.add_new_task(RangeTask::new(range as RangeFunc));
+    flow.set_task_name(&stats, "stats");
+
+    flow.connect_output0_to_input0(&source, &stats);
+    flow.connect_output0_to_input0(&stats, &range);
+    flow.connect_output1_to_input1(&stats, &range);
+
+    flow.record_output0(&source);
+    flow.record_output0(&stats);
+    flow.record_output1(&stats);
+    flow.record_output0(&range);
+
+    TestFlow {
+        flow,
+        source,
+        stats,
+        range,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn main() {
+    //
+    // record a run of the system
+    //
+    let recorded = build_flow();
+    let flow_exec = recorded.flow.execute().await;
+    assert_eq!(*flow_exec.get_task_output0(&recorded.range).unwrap(), 11);
+
+    let recording = flow_exec.get_recording().unwrap();
+    assert_eq!(recording.get_outputs().len(), 4);
+    let stats_max = recording.get_output(recorded.stats.id(), 1).unwrap();
+    assert_eq!(stats_max.task_name, "stats");
+    assert_eq!(stats_max.value, serde_json::json!(9));
+
+    let path = std::env::temp_dir().join(format!("rs_taskflow_replay_{}.json", std::process::id()));
+    recording.save(&path).unwrap();
+    let recording = Recording::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(recording, flow_exec.get_recording().unwrap());
+
+    //
+    // replay only the last task of a freshly built system
+    //
+    let source_runs = SOURCE_RUNS.load(Ordering::SeqCst);
+    let stats_runs = STATS_RUNS.load(Ordering::SeqCst);
+
+    let replayed = build_flow();
+    let flow_exec = replayed
+        .flow
+        .replay(&recording, &[replayed.range.id()])
+        .unwrap()
+        .await;
+    assert_eq!(*flow_exec.get_task_output0(&replayed.range).unwrap(), 11);
+    assert_eq!(
+        flow_exec.get_task_status(replayed.range.id()),
+        TaskStatus::Completed
+    );
+    assert_eq!(
+        flow_exec.get_task_status(replayed.stats.id()),
+        TaskStatus::Replayed
+    );
+    assert_eq!(
+        flow_exec.get_task_status(replayed.source.id()),
+        TaskStatus::Replayed
+    );
+    assert_eq!(SOURCE_RUNS.load(Ordering::SeqCst), source_runs);
+    assert_eq!(STATS_RUNS.load(Ordering::SeqCst), stats_runs);
+
+    //
+    // replay the last two tasks with a tampered recording
+    //
+    let mut value = serde_json::to_value(&recording).unwrap();
+    for output in value["outputs"].as_array_mut().unwrap() {
+        if output["task_id"] == replayed.source.id() {
+            output["value"] = serde_json::json!([100, 50]);
+        }
+    }
+    let tampered: Recording = serde_json::from_value(value).unwrap();
+    let flow_exec = replayed
+        .flow
+        .replay(&tampered, &[replayed.stats.id(), replayed.range.id()])
+        .unwrap()
+        .await;
+    assert_eq!(*flow_exec.get_task_output0(&replayed.range).unwrap(), 50);
+    assert_eq!(SOURCE_RUNS.load(Ordering::SeqCst), source_runs);
+
+    //
+    // replay a task in the middle, whose dependant is left out rather than replayed
+    //
+    let flow_exec = replayed
+        .flow
+        .replay(&recording, &[replayed.stats.id()])
+        .unwrap()
+        .await;
+    assert_eq!(
+        flow_exec.get_task_status(replayed.source.id()),
+        TaskStatus::Replayed
+    );
+    assert_eq!(
+        flow_exec.get_task_status(replayed.range.id()),
+        TaskStatus::Excluded
+    );
+    assert_eq!(flow_exec.get_task_output0(&replayed.range), None);
+}
+
+#[test]
+fn replay_errors() {
+    let test_flow = build_flow();
+    let range_id = test_flow.range.id();
+    let stats_id = test_flow.stats.id();
+
+    match test_flow
+        .flow
+        .replay(&Recording::default(), &[range_id, 42])
+    {
+        Err(ReplayError::UnknownTask { task_id: 42 }) => {}
+        _ => panic!("expected an unknown task"),
+    }
+
+    match test_flow.flow.replay(&Recording::default(), &[range_id]) {
+        Err(ReplayError::MissingOutput { task_id, port: 0 }) => assert_eq!(task_id, stats_id),
+        _ => panic!("expected a missing output"),
+    }
+
+    let invalid: Recording = serde_json::from_value(serde_json::json!({
+        "outputs": [
+            {"task_id": stats_id, "task_name": "stats", "port": 0, "value": "not a number"},
+            {"task_id": stats_id, "task_name": "stats", "port": 1, "value": 1},
+        ]
+    }))
+    .unwrap();
+    match test_flow.flow.replay(&invalid, &[range_id]) {
+        Err(ReplayError::InvalidOutput {
+            task_id, port: 0, ..
+        }) => assert_eq!(task_id, stats_id),
+        _ => panic!("expected an invalid output"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn unserializable_output() {
+    let mut flow = Flow::new();
+    let pairs = flow.add_new_task(PairsTask::new(|| BTreeMap::from([((1, 2), 3)])));
+    flow.record_output0(&pairs);
+
+    // JSON object keys have to be strings, so the tuple keys cannot be recorded
+    match flow.execute().await.get_recording() {
+        Err(ReplayError::UnserializableOutput {
+            task_id, port: 0, ..
+        }) => assert_eq!(task_id, pairs.id()),
+        _ => panic!("expected an unserializable output"),
+    }
+}
@@ -0,0 +1,73 @@
+use std::thread;
+use std::time::Duration;
+
+use rs_taskflow::flow::Flow;
+use rs_taskflow::task::*;
+
+#[rs_taskflow_derive::derive_task((), (i32,))]
+struct SourceTask;
+
+#[rs_taskflow_derive::derive_task((i32,), (i32,))]
+struct SleepTask;
+
+#[rs_taskflow_derive::derive_task((i32, i32), (i32,))]
+struct SinkTask;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn main() {
+    //
+    // declare system
+    //
+    let mut flow = Flow::new();
+
+    let source = flow.add_new_task(SourceTask::new(|| 1));
+    let slow = flow.add_new_task(SleepTask::new(|x: &i32| {
+        thread::sleep(Duration::from_millis(60));
+        x + 1
+    }));
+    let fast = flow.add_new_task(SleepTask::new(|x: &i32| {
+        thread::sleep(Duration::from_millis(5));
+        x + 2
+    }));
+    let sink = flow.add_new_task(SinkTask::new(|a: &i32, b: &i32| a + b));
+    flow.set_task_name(&source, "source");
+    flow.set_task_name(&slow, "slow");
+
+    flow.connect_output0_to_input0(&source, &slow);
+    flow.connect_output0_to_input0(&source, &fast);
+    flow.connect_output0_to_input0(&slow, &sink);
+    flow.connect_output0_to_input1(&fast, &sink);
+
+    //
+    // starting running the system
+    //
+    let flow_exec = flow.execute().await;
+    assert_eq!(*flow_exec.get_task_output0(&sink).unwrap(), 5);
+
+    //
+    // check the report
+    //
+    let report = flow_exec.get_report();
+    assert_eq!(report.get_task_timings().len(), 4);
+    assert_eq!(report.get_task_timings()[0].task_id, source.id());
+    assert_eq!(report.get_task_timings()[3].task_id, sink.id());
+    assert!(report.get_task_timing(slow.id()).unwrap().duration >= Duration::from_millis(60));
+
+    assert_eq!(
+        report.get_critical_path(),
+        &[source.id(), slow.id(), sink.id()]
+    );
+    assert!(report.get_critical_path_time() >= Duration::from_millis(60));
+    assert!(report.get_critical_path_time() <= report.get_total_time());
+
+    let workers = report.get_worker_utilisations();
+    assert!(!workers.is_empty());
+    assert_eq!(workers.iter().map(|w| w.num_tasks).sum::<usize>(), 4);
+    assert!(workers
+        .iter()
+        .all(|w| w.utilisation >= 0.0 && w.utilisation <= 1.0));
+
+    let text = report.to_string();
+    assert!(text.contains("critical path ("));
+    assert!(text.contains(&format!("source (0) -> slow (1) -> task {}", sink.id())));
+}
@@ -0,0 +1,94 @@
+use rs_taskflow::execution::TaskStatus;
+use rs_taskflow::flow::Flow;
+use rs_taskflow::task::*;
+
+#[rs_taskflow_derive::derive_task((), (u64,))]
+struct SourceTask;
+
+#[rs_taskflow_derive::derive_task((u64,), (u64,))]
+struct IncrementTask;
+
+#[rs_taskflow_derive::derive_task((u64, u64), (u64,))]
+struct SumTask;
+
+fn increment(x: &u64) -> u64 {
+    x + 1
+}
+
+fn add(a: &u64, b: &u64) -> u64 {
+    a + b
+}
+
+type IncrementFunc = fn(&u64) -> u64;
+type AddFunc = fn(&u64, &u64) -> u64;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn long_chain() {
+    const LEN: u64 = 10_000;
+
+    let mut flow = Flow::new();
+
+    let source = flow.add_new_task(SourceTask::new(|| 0));
+    let mut last = flow.add_new_task(IncrementTask::new(increment as IncrementFunc));
+    flow.connect_output0_to_input0(&source, &last);
+    for _ in 1..LEN {
+        let next = flow.add_new_task(IncrementTask::new(increment as IncrementFunc));
+        flow.connect_output0_to_input0(&last, &next);
+        last = next;
+    }
+
+    let flow_exec = flow.execute().await;
+    assert_eq!(*flow_exec.get_task_output0(&last).unwrap(), LEN);
+    assert_eq!(
+        flow_exec.get_task_status(source.id()),
+        TaskStatus::Completed
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn wide_fan_out() {
+    const WIDTH: u64 = 8192; // a power of two, so the tree is balanced
+
+    //
+    // a source feeding a layer of increments that is summed up by a binary tree
+    //
+    let mut flow = Flow::new();
+
+    let source = flow.add_new_task(SourceTask::new(|| 1));
+    let mut layer = Vec::new();
+    for _ in 0..WIDTH {
+        let task = flow.add_new_task(IncrementTask::new(increment as IncrementFunc));
+        flow.connect_output0_to_input0(&source, &task);
+        layer.push(task);
+    }
+    let first = layer[0].id();
+    let mut sums = Vec::new();
+    for pair in layer.chunks(2) {
+        let sum = flow.add_new_task(SumTask::new(add as AddFunc));
+        flow.connect_output0_to_input0(&pair[0], &sum);
+        flow.connect_output0_to_input1(&pair[1], &sum);
+        sums.push(sum);
+    }
+    while sums.len() > 1 {
+        let mut next_sums = Vec::new();
+        let mut tasks = sums.into_iter();
+        while let (Some(a), Some(b)) = (tasks.next(), tasks.next()) {
+            let sum = flow.add_new_task(SumTask::new(add as AddFunc));
+            flow.connect_output0_to_input0(&a, &sum);
+            flow.connect_output0_to_input1(&b, &sum);
+            next_sums.push(sum);
+        }
+        sums = next_sums;
+    }
+
+    let flow_exec = flow.execute().await;
+    assert_eq!(*flow_exec.get_task_output0(&sums[0]).unwrap(), 2 * WIDTH);
+    assert_eq!(flow_exec.get_task_status(first), TaskStatus::Completed);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn empty_flow() {
+    let flow = Flow::new();
+    let flow_exec = flow.execute().await;
+    assert!(flow_exec.get_report().get_task_timings().is_empty());
+}
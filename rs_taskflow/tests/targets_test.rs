@@ -0,0 +1,78 @@
+use rs_taskflow::execution::TaskStatus;
+use rs_taskflow::flow::{Flow, UnknownTaskError};
+use rs_taskflow::task::*;
+
+#[rs_taskflow_derive::derive_task((), (i32,))]
+struct SourceTask;
+
+#[rs_taskflow_derive::derive_task((i32,), (i32,))]
+struct ForwardTask;
+
+#[rs_taskflow_derive::derive_task((i32, i32), (i32,))]
+struct SumTask;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn main() {
+    //
+    // declare system
+    //
+    let mut flow = Flow::new();
+
+    let source_1 = flow.add_new_task(SourceTask::new(|| 1));
+    let source_2 = flow.add_new_task(SourceTask::new(|| 2));
+    let double = flow.add_new_task(ForwardTask::new(|x: &i32| x * 2));
+    let negate = flow.add_new_task(ForwardTask::new(|x: &i32| -x));
+    let sum = flow.add_new_task(SumTask::new(|a: &i32, b: &i32| a + b));
+    let unused = flow.add_new_task(ForwardTask::new(|_: &i32| -> i32 {
+        panic!("not a target")
+    }));
+
+    flow.connect_output0_to_input0(&source_1, &double);
+    flow.connect_output0_to_input0(&source_2, &negate);
+    flow.connect_output0_to_input0(&double, &sum);
+    flow.connect_output0_to_input1(&source_2, &sum);
+    flow.connect_output0_to_input0(&negate, &unused);
+
+    //
+    // run only what the sum needs
+    //
+    let flow_exec = flow.execute_targets(&[sum.id()]).unwrap().await;
+
+    assert_eq!(*flow_exec.get_task_output0(&sum).unwrap(), 4);
+    assert_eq!(*flow_exec.get_task_output0(&double).unwrap(), 2);
+    assert_eq!(flow_exec.get_task_output0(&negate), None);
+    assert_eq!(flow_exec.get_task_output0(&unused), None);
+
+    for task_id in [source_1.id(), source_2.id(), double.id(), sum.id()] {
+        assert_eq!(flow_exec.get_task_status(task_id), TaskStatus::Completed);
+    }
+    for task_id in [negate.id(), unused.id()] {
+        assert_eq!(flow_exec.get_task_status(task_id), TaskStatus::Excluded);
+    }
+    assert_eq!(flow_exec.get_report().get_task_timings().len(), 4);
+
+    //
+    // several targets run the union of what they need
+    //
+    let flow_exec = flow
+        .execute_targets(&[double.id(), negate.id()])
+        .unwrap()
+        .await;
+    assert_eq!(*flow_exec.get_task_output0(&negate).unwrap(), -2);
+    assert_eq!(flow_exec.get_task_status(sum.id()), TaskStatus::Excluded);
+    assert_eq!(flow_exec.get_task_status(unused.id()), TaskStatus::Excluded);
+    assert!(flow_exec
+        .to_dot()
+        .contains("fillcolor=\"whitesmoke\", tooltip=\"Excluded\""));
+}
+
+#[test]
+fn unknown_target() {
+    let mut flow = Flow::new();
+    let source = flow.add_new_task(SourceTask::new(|| 1));
+
+    match flow.execute_targets(&[source.id(), 7]) {
+        Err(error) => assert_eq!(error, UnknownTaskError { task_id: 7 }),
+        Ok(_) => panic!("expected an unknown task"),
+    }
+}
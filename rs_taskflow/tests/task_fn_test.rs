@@ -0,0 +1,93 @@
+use rs_taskflow::flow::Flow;
+use rs_taskflow::task::*;
+use rs_taskflow_derive::task;
+
+#[task]
+fn number() -> i64 {
+    21
+}
+
+#[task(OtherNumberTask)]
+fn other_number() -> i64 {
+    4
+}
+
+#[task]
+fn split(number: &i64) -> (bool, i64) {
+    (number % 2 == 1, number * 2)
+}
+
+#[task]
+fn combine(
+    is_odd: &bool,
+    doubled: Option<&i64>,
+    #[default(100)] offset: &i64,
+    #[join(first_completed)] first: &i64,
+    all: Vec<&i64>,
+) -> String {
+    let total = doubled.unwrap_or(&0) + offset + all.into_iter().sum::<i64>();
+    format!("{} {} {}", is_odd, first, total)
+}
+
+#[task]
+fn negate(#[join(unique)] number: &i64) -> i64 {
+    -number
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn main() {
+    //
+    // declare system
+    //
+    let mut flow = Flow::new();
+
+    let number_task = flow.add_new_task(NumberTask::default());
+    let other_task = flow.add_new_task(OtherNumberTask::default());
+    let split_task = flow.add_new_task(SplitTask::default());
+    let combine_task = flow.add_new_task(CombineTask::default());
+
+    flow.connect_output0_to_input0(&number_task, &split_task);
+    flow.connect_output0_to_input0(&split_task, &combine_task);
+    flow.connect_output1_to_input1(&split_task, &combine_task);
+    flow.connect_output0_to_input3(&number_task, &combine_task);
+    flow.connect_output0_to_input4(&number_task, &combine_task);
+    flow.connect_output0_to_input4(&other_task, &combine_task);
+
+    let inputs = combine_task.inputs();
+    assert_eq!(inputs.offset.get_port(), 2);
+    assert_eq!(inputs.all.get_port(), 4);
+
+    //
+    // starting running the system
+    //
+    let flow_exec = flow.execute().await;
+
+    //
+    // check the results of the system
+    //
+    assert!(*flow_exec.get_task_output0(&split_task).unwrap());
+    assert_eq!(*flow_exec.get_task_output1(&split_task).unwrap(), 42);
+    assert_eq!(
+        flow_exec.get_task_output0(&combine_task).unwrap(),
+        "true 21 167"
+    );
+}
+
+#[test]
+fn keeps_function() {
+    assert_eq!(split(&3), (true, 6));
+    assert_eq!(combine(&false, None, &1, &2, vec![&3]), "false 2 4");
+}
+
+#[test]
+#[should_panic(expected = "already has a producer")]
+fn unique_parameter() {
+    let mut flow = Flow::new();
+
+    let number_task = flow.add_new_task(NumberTask::default());
+    let other_task = flow.add_new_task(OtherNumberTask::default());
+    let negate_task = flow.add_new_task(NegateTask::default());
+
+    flow.connect_output0_to_input0(&number_task, &negate_task);
+    flow.connect_output0_to_input0(&other_task, &negate_task);
+}
@@ -0,0 +1,149 @@
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+use rs_taskflow::flow::Flow;
+use rs_taskflow::task::*;
+
+#[rs_taskflow_derive::derive_task((), (i32,))]
+struct SourceTask;
+
+#[rs_taskflow_derive::derive_task((i32,), (i32,))]
+struct ForwardTask;
+
+#[derive(Debug, Default, PartialEq)]
+struct RecordedSpan {
+    name: &'static str,
+    parent: Option<u64>,
+    fields: Vec<(&'static str, String)>,
+}
+
+struct FieldRecorder<'a>(&'a mut Vec<(&'static str, String)>);
+
+impl Visit for FieldRecorder<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.push((field.name(), format!("{:?}", value)));
+    }
+}
+
+#[derive(Default)]
+struct RecordingSubscriber {
+    next_id: AtomicU64,
+    spans: Mutex<Vec<RecordedSpan>>,
+    events: Mutex<Vec<(Option<u64>, String)>>,
+    current: Mutex<Vec<u64>>,
+}
+
+impl Subscriber for RecordingSubscriber {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let mut recorded = RecordedSpan {
+            name: span.metadata().name(),
+            parent: span.parent().map(|parent| parent.into_u64()),
+            ..Default::default()
+        };
+        span.record(&mut FieldRecorder(&mut recorded.fields));
+        self.spans.lock().unwrap().push(recorded);
+        Id::from_u64(id)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let parent = match event.parent() {
+            Some(parent) => Some(parent.into_u64()),
+            None => self.current.lock().unwrap().last().copied(),
+        };
+        let mut fields = Vec::new();
+        event.record(&mut FieldRecorder(&mut fields));
+        let message = fields
+            .into_iter()
+            .find(|(name, _)| *name == "message")
+            .map(|(_, value)| value)
+            .unwrap_or_default();
+        self.events.lock().unwrap().push((parent, message));
+    }
+
+    fn enter(&self, span: &Id) {
+        self.current.lock().unwrap().push(span.into_u64());
+    }
+
+    fn exit(&self, _span: &Id) {
+        self.current.lock().unwrap().pop();
+    }
+}
+
+#[test]
+fn main() {
+    let subscriber = Arc::new(RecordingSubscriber::default());
+
+    //
+    // declare system
+    //
+    let mut flow = Flow::new();
+
+    let source = flow.add_new_task(SourceTask::new(|| 1));
+    let forward = flow.add_new_task(ForwardTask::new(|x: &i32| x + 1));
+    flow.set_task_name(&source, "source");
+    flow.set_task_name(&forward, "forward");
+
+    flow.connect_output0_to_input0(&source, &forward);
+
+    //
+    // starting running the system on a single thread, so every span is seen by the subscriber
+    //
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let flow_exec =
+        tracing::subscriber::with_default(subscriber.clone(), || runtime.block_on(flow.execute()));
+    assert_eq!(*flow_exec.get_task_output0(&forward).unwrap(), 2);
+
+    //
+    // check the spans and events that were emitted
+    //
+    let spans = subscriber.spans.lock().unwrap();
+    assert_eq!(spans.len(), 3);
+    assert_eq!(spans[0].name, "execution");
+    assert_eq!(spans[0].fields, vec![("num_tasks", String::from("2"))]);
+
+    let task_span = |name: &str| {
+        let position = spans
+            .iter()
+            .position(|span| span.fields.contains(&("task_name", format!("{:?}", name))))
+            .unwrap();
+        (position as u64 + 1, &spans[position])
+    };
+    let (source_span_id, source_span) = task_span("source");
+    let (forward_span_id, forward_span) = task_span("forward");
+    assert_eq!(source_span.name, "task");
+    assert_eq!(source_span.parent, Some(1));
+    assert!(source_span
+        .fields
+        .contains(&("task_id", source.id().to_string())));
+    assert!(source_span
+        .fields
+        .contains(&("num_dependants", String::from("1"))));
+    assert!(forward_span
+        .fields
+        .contains(&("num_dependencies", String::from("1"))));
+
+    let events = subscriber.events.lock().unwrap();
+    let has_event =
+        |span_id: u64, message: &str| events.contains(&(Some(span_id), String::from(message)));
+    assert!(has_event(1, "execution started"));
+    assert!(has_event(1, "execution finished"));
+    assert!(has_event(source_span_id, "completed"));
+    assert!(has_event(forward_span_id, "executing"));
+    assert!(has_event(forward_span_id, "completed"));
+}
@@ -0,0 +1,6 @@
+use rs_taskflow_derive::derive_task;
+
+#[derive_task((i32,), (i32,), failure = String)]
+struct BadErrorTask;
+
+fn main() {}
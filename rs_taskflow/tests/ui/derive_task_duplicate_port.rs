@@ -0,0 +1,6 @@
+use rs_taskflow_derive::derive_task;
+
+#[derive_task((value: i32, value: i32), (i32,))]
+struct DuplicatePortTask;
+
+fn main() {}
@@ -0,0 +1,8 @@
+use rs_taskflow_derive::derive_task;
+
+#[derive_task((i32,), (i32,))]
+struct ClashTask {
+    output0: i32,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use rs_taskflow_derive::derive_task;
+
+#[derive_task]
+struct NoPortsTask;
+
+fn main() {}
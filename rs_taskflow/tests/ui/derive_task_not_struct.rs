@@ -0,0 +1,8 @@
+use rs_taskflow_derive::derive_task;
+
+#[derive_task((i32,), (i32,))]
+enum NotAStruct {
+    A,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use rs_taskflow_derive::derive_task;
+
+#[derive_task((i32, i32, i32, i32, i32, i32, i32, i32, i32, i32, u8), (i32,))]
+struct TooManyInputsTask;
+
+fn main() {}
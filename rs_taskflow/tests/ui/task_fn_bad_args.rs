@@ -0,0 +1,8 @@
+use rs_taskflow_derive::task;
+
+#[task(FirstTask, SecondTask)]
+fn value() -> i32 {
+    1
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use rs_taskflow_derive::task;
+
+#[task(error = std::io::Error)]
+fn value() -> i32 {
+    1
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use rs_taskflow_derive::task;
+
+#[task]
+fn identity<T: Clone>(value: &T) -> T {
+    value.clone()
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use rs_taskflow_derive::task;
+
+#[task]
+fn increment(value: &mut i32) -> i32 {
+    *value + 1
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use rs_taskflow_derive::task;
+
+#[task]
+fn wrap(value: &i32) -> (i32,) {
+    (*value,)
+}
+
+fn main() {}
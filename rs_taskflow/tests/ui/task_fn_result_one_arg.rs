@@ -0,0 +1,8 @@
+use rs_taskflow_derive::task;
+
+#[task]
+fn read_value() -> std::io::Result<i32> {
+    Ok(1)
+}
+
+fn main() {}
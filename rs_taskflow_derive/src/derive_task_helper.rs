@@ -5,47 +5,391 @@ extern crate syn;
 use quote::quote;
 use syn::parse::Parse;
 
+pub(crate) enum TaskInputKind {
+    Required,
+    Optional,
+    Default(syn::Expr),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TaskInputJoin {
+    Replace,
+    Unique,
+    Collect,
+    FirstCompleted,
+}
+
+// parses the `name:` in front of the type of a named port
+fn parse_port_name(input: syn::parse::ParseStream) -> syn::parse::Result<Option<syn::Ident>> {
+    if input.peek(syn::Ident) && input.peek2(syn::Token![:]) && !input.peek2(syn::Token![::]) {
+        let name = input.parse::<syn::Ident>()?;
+        input.parse::<syn::Token![:]>()?;
+        Ok(Some(name))
+    } else {
+        Ok(None)
+    }
+}
+
+pub(crate) struct TaskInputPort {
+    name: Option<syn::Ident>,
+    port_type: syn::Type,
+    kind: TaskInputKind,
+    join: TaskInputJoin,
+}
+
+impl Parse for TaskInputPort {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let name = parse_port_name(input)?;
+        let port_type = input.parse::<syn::Type>()?;
+
+        let mut kind = TaskInputKind::Required;
+        let mut join = TaskInputJoin::Replace;
+        for attr in attrs {
+            if attr.path().is_ident("optional") {
+                attr.meta.require_path_only()?;
+                kind = TaskInputKind::Optional;
+            } else if attr.path().is_ident("join") {
+                let policy = attr.parse_args::<syn::Ident>()?;
+                join = if policy == "unique" {
+                    TaskInputJoin::Unique
+                } else if policy == "collect" {
+                    TaskInputJoin::Collect
+                } else if policy == "first_completed" {
+                    TaskInputJoin::FirstCompleted
+                } else {
+                    return Err(syn::Error::new_spanned(
+                        policy,
+                        "unknown join policy, expected `unique`, `collect` or `first_completed`",
+                    ));
+                };
+            } else {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "unknown input port attribute, expected `#[optional]` or `#[join(...)]`",
+                ));
+            }
+        }
+
+        if input.peek(syn::Token![=]) {
+            let eq_token = input.parse::<syn::Token![=]>()?;
+            let default_value = input.parse::<syn::Expr>()?;
+            if let TaskInputKind::Optional = kind {
+                return Err(syn::Error::new_spanned(
+                    eq_token,
+                    "an optional input port cannot also have a default value",
+                ));
+            }
+            kind = TaskInputKind::Default(default_value);
+        }
+
+        if join == TaskInputJoin::Collect && !matches!(kind, TaskInputKind::Required) {
+            return Err(syn::Error::new_spanned(
+                port_type,
+                "a collecting input port is empty when unconnected and cannot be optional or have a default value",
+            ));
+        }
+
+        Ok(TaskInputPort {
+            name,
+            port_type,
+            kind,
+            join,
+        })
+    }
+}
+
+impl TaskInputPort {
+    pub(crate) fn new(
+        name: Option<syn::Ident>,
+        port_type: syn::Type,
+        kind: TaskInputKind,
+        join: TaskInputJoin,
+    ) -> Self {
+        Self {
+            name,
+            port_type,
+            kind,
+            join,
+        }
+    }
+
+    fn get_handle_field_name(&self, index: usize) -> syn::Ident {
+        match self.join {
+            TaskInputJoin::Replace | TaskInputJoin::Unique => {
+                quote::format_ident!("input{}_handle", index)
+            }
+            _ => quote::format_ident!("input{}_handles", index),
+        }
+    }
+
+    fn get_struct_fields(&self, index: usize) -> proc_macro2::TokenStream {
+        let input_type = &self.port_type;
+        let field_name = self.get_handle_field_name(index);
+        let mut struct_fields = match self.join {
+            TaskInputJoin::Replace | TaskInputJoin::Unique => {
+                quote! {#field_name: Option<TaskInputHandle<#input_type>>,}
+            }
+            _ => quote! {#field_name: Vec<TaskInputHandle<#input_type>>,},
+        };
+        if let TaskInputKind::Default(_) = self.kind {
+            let default_name = quote::format_ident!("input{}_default", index);
+            struct_fields.extend(quote! {#default_name: #input_type,});
+        }
+        if self.join == TaskInputJoin::FirstCompleted {
+            let producer_name = quote::format_ident!("input{}_producer", index);
+            struct_fields.extend(quote! {#producer_name: Option<usize>,});
+        }
+        struct_fields
+    }
+
+    fn get_struct_field_inits(&self, index: usize) -> proc_macro2::TokenStream {
+        let field_name = self.get_handle_field_name(index);
+        let mut struct_field_init = match self.join {
+            TaskInputJoin::Replace | TaskInputJoin::Unique => quote! {#field_name: None,},
+            _ => quote! {#field_name: Vec::new(),},
+        };
+        if let TaskInputKind::Default(default_value) = &self.kind {
+            let default_name = quote::format_ident!("input{}_default", index);
+            struct_field_init.extend(quote! {#default_name: #default_value,});
+        }
+        if self.join == TaskInputJoin::FirstCompleted {
+            let producer_name = quote::format_ident!("input{}_producer", index);
+            struct_field_init.extend(quote! {#producer_name: None,});
+        }
+        struct_field_init
+    }
+
+    fn get_func_param(&self) -> proc_macro2::TokenStream {
+        let input_type = &self.port_type;
+        match (self.join, &self.kind) {
+            (TaskInputJoin::Collect, _) => quote! {Vec<&#input_type>},
+            (_, TaskInputKind::Optional) => quote! {Option<&#input_type>},
+            _ => quote! {&#input_type},
+        }
+    }
+
+    fn get_join_policy(&self) -> proc_macro2::TokenStream {
+        match self.join {
+            TaskInputJoin::Replace => quote! {JoinPolicy::Replace},
+            TaskInputJoin::Unique => quote! {JoinPolicy::Unique},
+            TaskInputJoin::Collect => quote! {JoinPolicy::Collect},
+            TaskInputJoin::FirstCompleted => quote! {JoinPolicy::FirstCompleted},
+        }
+    }
+
+    fn get_input_value(&self, index: usize) -> proc_macro2::TokenStream {
+        let field_name = self.get_handle_field_name(index);
+        let value_name = quote::format_ident!("input{}_value", index);
+
+        let input_value = match self.join {
+            TaskInputJoin::Replace | TaskInputJoin::Unique => quote! {
+                self.#field_name.as_ref().map(|input| input.get_value(flow).unwrap())
+            },
+            TaskInputJoin::Collect => {
+                return quote! {
+                    let #value_name: Vec<_> = self
+                        .#field_name
+                        .iter()
+                        .map(|input| input.get_value(flow).unwrap())
+                        .collect();
+                };
+            }
+            // only the producer that the scheduler waited for is read, see `set_input_producer()`;
+            // the flow connects at most one output of each producer task to such an input
+            TaskInputJoin::FirstCompleted => {
+                let producer_name = quote::format_ident!("input{}_producer", index);
+                quote! {
+                    self.#field_name
+                        .iter()
+                        .find(|input| Some(input.get_source_task_id()) == self.#producer_name)
+                        .map(|input| input.get_value(flow).unwrap())
+                }
+            }
+        };
+
+        match &self.kind {
+            TaskInputKind::Required => {
+                let missing_msg = match self.join {
+                    TaskInputJoin::FirstCompleted => {
+                        format!("input {} has no completed producer", index)
+                    }
+                    _ => format!("input {} is not connected", index),
+                };
+                quote! {
+                    let #value_name = match #input_value {
+                        Some(value) => value,
+                        None => panic!(#missing_msg),
+                    };
+                }
+            }
+            TaskInputKind::Optional => quote! {
+                let #value_name = #input_value;
+            },
+            TaskInputKind::Default(_) => {
+                let default_name = quote::format_ident!("input{}_default", index);
+                quote! {
+                    let #value_name = match #input_value {
+                        Some(value) => value,
+                        None => &self.#default_name,
+                    };
+                }
+            }
+        }
+    }
+
+    fn get_set_input(&self, index: usize) -> proc_macro2::TokenStream {
+        let field_name = self.get_handle_field_name(index);
+        match self.join {
+            TaskInputJoin::Replace | TaskInputJoin::Unique => {
+                quote! {self.#field_name = Some(task_input);}
+            }
+            _ => quote! {self.#field_name.push(task_input);},
+        }
+    }
+}
+
+pub(crate) struct TaskOutputPort {
+    name: Option<syn::Ident>,
+    port_type: syn::Type,
+}
+
+impl Parse for TaskOutputPort {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        let name = parse_port_name(input)?;
+        let port_type = input.parse::<syn::Type>()?;
+        Ok(TaskOutputPort { name, port_type })
+    }
+}
+
+// the number of `TaskInputN` and `TaskOutputN` traits rs_taskflow generates
+const MAX_NUM_PORTS: usize = 10;
+
+// port names must be unique among the inputs and among the outputs of a task
+fn check_port_names<'a, I: Iterator<Item = &'a Option<syn::Ident>>>(
+    names: I,
+) -> syn::parse::Result<()> {
+    let mut seen = Vec::<&syn::Ident>::new();
+    for name in names.flatten() {
+        if seen.contains(&name) {
+            return Err(syn::Error::new_spanned(
+                name,
+                format!("duplicate port name `{}`", name),
+            ));
+        }
+        seen.push(name);
+    }
+    Ok(())
+}
+
 pub(crate) struct TaskInterfaceOptions {
-    input_types: Vec<syn::Type>,
+    inputs: Vec<TaskInputPort>,
     output_types: Vec<syn::Type>,
+    output_names: Vec<Option<syn::Ident>>,
+    error_type: Option<syn::Type>, // the task function returns a `Result` with this error
+}
+
+// parses the `error = Type` following the ports of a fallible task
+fn parse_error_type(input: syn::parse::ParseStream) -> syn::parse::Result<Option<syn::Type>> {
+    if input.is_empty() {
+        return Ok(None);
+    }
+    input.parse::<syn::Token![,]>()?;
+    if input.is_empty() {
+        return Ok(None);
+    }
+
+    let expected = "expected `error = Type` after the input and output ports";
+    if !input.peek(syn::Ident) {
+        return Err(input.error(expected));
+    }
+    let key = input.parse::<syn::Ident>()?;
+    if key != "error" {
+        return Err(syn::Error::new_spanned(key, expected));
+    }
+    input.parse::<syn::Token![=]>()?;
+    let error_type = input.parse::<syn::Type>()?;
+    input.parse::<Option<syn::Token![,]>>()?;
+    Ok(Some(error_type))
 }
 
 impl Parse for TaskInterfaceOptions {
     fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
-        let types = input.parse_terminated(syn::Type::parse, syn::Token![,])?;
-        let types_vec: Vec<syn::Type> = types.clone().into_iter().collect();
-        if types_vec.len() != 2 {
-            return Err(syn::Error::new_spanned(types, "expected two tuples"));
+        if input.is_empty() {
+            return Err(syn::Error::new(
+                input.span(),
+                "expected the input and output ports, e.g. `#[derive_task((i32, i32), (i64,))]`",
+            ));
         }
 
-        let input_types = match types_vec.get(0).unwrap() {
-            syn::Type::Tuple(tuple) => Ok(tuple.elems.clone()),
-            t @ _ => Err(syn::Error::new_spanned(
-                t,
-                "expected a tuple of input types",
-            )),
-        }
-        .unwrap();
+        let input_content;
+        syn::parenthesized!(input_content in input);
+        let inputs = input_content.parse_terminated(TaskInputPort::parse, syn::Token![,])?;
 
-        let output_types = match types_vec.get(1).unwrap() {
-            syn::Type::Tuple(tuple) => Ok(tuple.elems.clone()),
-            t @ _ => Err(syn::Error::new_spanned(
-                t,
-                "expected a tuple of output types",
-            )),
+        input.parse::<syn::Token![,]>()?;
+
+        let output_content;
+        syn::parenthesized!(output_content in input);
+        let outputs = output_content.parse_terminated(TaskOutputPort::parse, syn::Token![,])?;
+
+        let error_type = parse_error_type(input)?;
+        if !input.is_empty() {
+            return Err(input.error("expected two tuples"));
         }
-        .unwrap();
 
-        Ok(TaskInterfaceOptions {
-            input_types: input_types.into_iter().collect(),
-            output_types: output_types.into_iter().collect(),
-        })
+        let inputs: Vec<TaskInputPort> = inputs.into_iter().collect();
+        let (output_names, output_types) = outputs
+            .into_iter()
+            .map(|output| (output.name, output.port_type))
+            .unzip();
+        check_port_names(inputs.iter().map(|input| &input.name))?;
+        let options = TaskInterfaceOptions {
+            inputs,
+            output_types,
+            output_names,
+            error_type,
+        };
+        check_port_names(options.output_names.iter())?;
+        options.check_num_ports()?;
+        Ok(options)
     }
 }
 
 impl TaskInterfaceOptions {
+    pub(crate) fn new(
+        inputs: Vec<TaskInputPort>,
+        output_types: Vec<syn::Type>,
+        error_type: Option<syn::Type>,
+    ) -> syn::Result<Self> {
+        let output_names = vec![None; output_types.len()];
+        let options = Self {
+            inputs,
+            output_types,
+            output_names,
+            error_type,
+        };
+        options.check_num_ports()?;
+        Ok(options)
+    }
+
+    fn check_num_ports(&self) -> syn::Result<()> {
+        if let Some(input) = self.inputs.get(MAX_NUM_PORTS) {
+            return Err(syn::Error::new_spanned(
+                &input.port_type,
+                format!("a task can have at most {} inputs", MAX_NUM_PORTS),
+            ));
+        }
+        if let Some(output_type) = self.output_types.get(MAX_NUM_PORTS) {
+            return Err(syn::Error::new_spanned(
+                output_type,
+                format!("a task can have at most {} outputs", MAX_NUM_PORTS),
+            ));
+        }
+        Ok(())
+    }
+
     pub(crate) fn get_num_inputs(&self) -> usize {
-        self.input_types.len()
+        self.inputs.len()
     }
 
     pub(crate) fn get_num_outputs(&self) -> usize {
@@ -55,14 +399,16 @@ impl TaskInterfaceOptions {
     fn get_struct_fields(&self) -> proc_macro2::TokenStream {
         let mut struct_fields = quote! {};
 
-        for (i, input_type) in self.input_types.iter().enumerate() {
-            let field_name = quote::format_ident!("input{}_handle", i);
-            struct_fields.extend(quote! {#field_name: Option<TaskInputHandle<#input_type>>,});
+        for (i, input) in self.inputs.iter().enumerate() {
+            struct_fields.extend(input.get_struct_fields(i));
         }
         for (i, output_type) in self.output_types.iter().enumerate() {
             let field_name = quote::format_ident!("output{}", i);
             struct_fields.extend(quote! {#field_name: Option<#output_type>,});
         }
+        if let Some(error_type) = &self.error_type {
+            struct_fields.extend(quote! {error: Option<std::sync::Arc<#error_type>>,});
+        }
         struct_fields.extend(quote! {func: FuncType});
 
         struct_fields
@@ -71,26 +417,34 @@ impl TaskInterfaceOptions {
     fn get_struct_field_inits(&self) -> proc_macro2::TokenStream {
         let mut struct_field_init = quote! {};
 
-        for i in 0..self.get_num_inputs() {
-            let field_name = quote::format_ident!("input{}_handle", i);
-            struct_field_init.extend(quote! {#field_name: None,})
+        for (i, input) in self.inputs.iter().enumerate() {
+            struct_field_init.extend(input.get_struct_field_inits(i));
         }
         for i in 0..self.get_num_outputs() {
             let field_name = quote::format_ident!("output{}", i);
             struct_field_init.extend(quote! {#field_name: None,})
         }
+        if self.error_type.is_some() {
+            struct_field_init.extend(quote! {error: None,});
+        }
         struct_field_init.extend(quote! {func: task_func});
 
         struct_field_init
     }
 
-    fn get_func_signature(&self) -> proc_macro2::TokenStream {
+    fn get_func_signature(
+        &self,
+        fields_type: Option<proc_macro2::TokenStream>,
+    ) -> proc_macro2::TokenStream {
         let mut input_params = quote! {};
-        for (i, input_type) in self.input_types.iter().enumerate() {
-            if i > 0 {
+        if let Some(fields_type) = fields_type {
+            input_params.extend(quote! {#fields_type});
+        }
+        for input in self.inputs.iter() {
+            if !input_params.is_empty() {
                 input_params.extend(quote! {,});
             }
-            input_params.extend(quote! {&#input_type})
+            input_params.extend(input.get_func_param());
         }
 
         let mut output_params = quote! {};
@@ -101,82 +455,334 @@ impl TaskInterfaceOptions {
             output_params.extend(quote! {#output_type})
         }
 
-        quote! {Fn(#input_params) -> (#output_params)}
+        match &self.error_type {
+            Some(error_type) => {
+                quote! {Fn(#input_params) -> std::result::Result<(#output_params), #error_type>}
+            }
+            None => quote! {Fn(#input_params) -> (#output_params)},
+        }
+    }
+
+    // the bounds on the task function in every impl for the task struct
+    fn get_func_bounds(&self, struct_options: &TaskStructOptions) -> proc_macro2::TokenStream {
+        let func_signature = self.get_func_signature(struct_options.get_fields_type());
+        quote! {'static + Clone + Send + Sync + #func_signature}
+    }
+
+    fn get_struct_field_names(&self) -> Vec<syn::Ident> {
+        let mut field_names = Vec::new();
+        for (i, input) in self.inputs.iter().enumerate() {
+            field_names.push(input.get_handle_field_name(i));
+            if let TaskInputKind::Default(_) = input.kind {
+                field_names.push(quote::format_ident!("input{}_default", i));
+            }
+            if input.join == TaskInputJoin::FirstCompleted {
+                field_names.push(quote::format_ident!("input{}_producer", i));
+            }
+        }
+        for i in 0..self.get_num_outputs() {
+            field_names.push(quote::format_ident!("output{}", i));
+        }
+        if self.error_type.is_some() {
+            field_names.push(quote::format_ident!("error"));
+        }
+        field_names.push(quote::format_ident!("func"));
+        field_names
+    }
+
+    fn get_join_policy_impl(&self) -> proc_macro2::TokenStream {
+        let mut policy_arms = quote! {};
+        let mut producer_arms = quote! {};
+        for (i, input) in self.inputs.iter().enumerate() {
+            if input.join != TaskInputJoin::Replace {
+                let join_policy = input.get_join_policy();
+                policy_arms.extend(quote! {#i => #join_policy,});
+            }
+            if input.join == TaskInputJoin::FirstCompleted {
+                let producer_name = quote::format_ident!("input{}_producer", i);
+                producer_arms.extend(quote! {#i => self.#producer_name = Some(producer_id),});
+            }
+        }
+
+        if policy_arms.is_empty() {
+            return quote! {};
+        }
+
+        let mut result = quote! {
+            fn get_input_join_policy(&self, port: usize) -> JoinPolicy {
+                match port {
+                    #policy_arms
+                    _ => JoinPolicy::Replace,
+                }
+            }
+        };
+        if !producer_arms.is_empty() {
+            result.extend(quote! {
+                fn set_input_producer(&mut self, port: usize, producer_id: usize) {
+                    match port {
+                        #producer_arms
+                        _ => {}
+                    }
+                }
+            });
+        }
+        result
     }
 }
 
 pub(crate) struct TaskStructOptions {
+    struct_attrs: Vec<syn::Attribute>,
     struct_name: syn::Ident,
     struct_visibility: syn::Visibility,
+    struct_generics: syn::Generics,
+    struct_fields: Vec<syn::Field>,
+}
+
+impl TaskStructOptions {
+    pub(crate) fn new(struct_name: syn::Ident, struct_visibility: syn::Visibility) -> Self {
+        Self {
+            struct_attrs: Vec::new(),
+            struct_name,
+            struct_visibility,
+            struct_generics: syn::Generics::default(),
+            struct_fields: Vec::new(),
+        }
+    }
+
+    //
+    // The generics of the user's struct with the type of the task function added, ahead of any
+    // parameters with defaults since those have to come last.
+    //
+    fn get_generics(&self, func_bounds: Option<&proc_macro2::TokenStream>) -> syn::Generics {
+        let func_param: syn::GenericParam = match func_bounds {
+            Some(func_bounds) => syn::parse_quote! {FuncType: #func_bounds},
+            None => syn::parse_quote! {FuncType},
+        };
+
+        let mut generics = self.struct_generics.clone();
+        let index = generics
+            .params
+            .iter()
+            .position(|param| match param {
+                syn::GenericParam::Type(param) => param.default.is_some(),
+                syn::GenericParam::Const(param) => param.default.is_some(),
+                syn::GenericParam::Lifetime(_) => false,
+            })
+            .unwrap_or(generics.params.len());
+        generics.params.insert(index, func_param);
+        generics
+    }
+
+    //
+    // The user's fields are handed to the task function as its first argument, borrowed through a
+    // struct named after the task, e.g. `TaskCFields` for a `TaskC`. Tasks without fields of
+    // their own keep the plain signature.
+    //
+    fn get_fields_name(&self) -> Option<syn::Ident> {
+        if self.struct_fields.is_empty() {
+            None
+        } else {
+            Some(quote::format_ident!("{}Fields", self.struct_name))
+        }
+    }
+
+    // the type of the fields argument in the signature of the task function
+    fn get_fields_type(&self) -> Option<proc_macro2::TokenStream> {
+        let fields_name = self.get_fields_name()?;
+        let params = self.struct_generics.params.iter().map(|param| match param {
+            syn::GenericParam::Lifetime(param) => {
+                let lifetime = &param.lifetime;
+                quote! {#lifetime}
+            }
+            syn::GenericParam::Type(param) => {
+                let ident = &param.ident;
+                quote! {#ident}
+            }
+            syn::GenericParam::Const(param) => {
+                let ident = &param.ident;
+                quote! {#ident}
+            }
+        });
+        Some(quote! {#fields_name<'_ #(, #params)*>})
+    }
+
+    // the value of the fields argument, borrowing from `self`
+    fn get_fields_init(&self) -> Option<proc_macro2::TokenStream> {
+        let fields_name = self.get_fields_name()?;
+        let field_names = self.get_field_names();
+        Some(quote! {#fields_name { #(#field_names: &self.#field_names,)* }})
+    }
+
+    fn get_field_names(&self) -> impl Iterator<Item = &syn::Ident> {
+        self.struct_fields
+            .iter()
+            .filter_map(|field| field.ident.as_ref())
+    }
+
+    // whether the user's struct already derives `Clone`, which the generated struct needs
+    fn derives_clone(&self) -> bool {
+        self.struct_attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("derive"))
+            .filter_map(|attr| {
+                attr.parse_args_with(
+                    syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated,
+                )
+                .ok()
+            })
+            .flatten()
+            .any(|path| {
+                path.segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "Clone")
+            })
+    }
 }
 
 impl Parse for TaskStructOptions {
     fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
         let def = input.parse::<syn::Item>()?;
         let struct_def = match def {
-            syn::Item::Struct(s) => Ok(s),
-            t @ _ => Err(syn::Error::new_spanned(t, "expected a struct item")),
-        }
-        .unwrap();
+            syn::Item::Struct(s) => s,
+            t => {
+                return Err(syn::Error::new_spanned(
+                    t,
+                    "`derive_task` can only be applied to a struct",
+                ))
+            }
+        };
+
+        let struct_fields = match struct_def.fields {
+            syn::Fields::Named(fields) => fields.named.into_iter().collect(),
+            syn::Fields::Unit => Vec::new(),
+            fields @ syn::Fields::Unnamed(_) => {
+                return Err(syn::Error::new_spanned(
+                    fields,
+                    "expected a struct with named fields or a unit struct",
+                ))
+            }
+        };
 
         Ok(TaskStructOptions {
+            struct_attrs: struct_def.attrs,
             struct_name: struct_def.ident,
             struct_visibility: struct_def.vis,
+            struct_generics: struct_def.generics,
+            struct_fields,
         })
     }
 }
 
+// everything `derive_task` generates for a task struct
+pub(crate) fn generate_task(
+    struct_options: &TaskStructOptions,
+    iface_options: &TaskInterfaceOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let generated_names = iface_options.get_struct_field_names();
+    for field_name in struct_options.get_field_names() {
+        if generated_names.contains(field_name) {
+            return Err(syn::Error::new_spanned(
+                field_name,
+                format!(
+                    "field `{}` clashes with a field generated by `derive_task`",
+                    field_name
+                ),
+            ));
+        }
+    }
+
+    let mut result = quote! {};
+    result.extend(generate_task_struct_impls(struct_options, iface_options));
+    result.extend(generate_task_ports_impl(struct_options, iface_options));
+    for i in 0..iface_options.get_num_inputs() {
+        result.extend(generate_task_input_impl(struct_options, iface_options, i));
+    }
+    for i in 0..iface_options.get_num_outputs() {
+        result.extend(generate_task_output_impl(struct_options, iface_options, i));
+    }
+    result.extend(generate_erased_ports_impl(struct_options, iface_options));
+    Ok(result)
+}
+
 pub(crate) fn generate_task_struct_impls(
     struct_options: &TaskStructOptions,
     iface_options: &TaskInterfaceOptions,
 ) -> proc_macro2::TokenStream {
     let TaskStructOptions {
+        struct_attrs,
         struct_name,
         struct_visibility: struct_visbility,
+        struct_generics: _,
+        struct_fields,
     } = struct_options;
 
-    let struct_fields = iface_options.get_struct_fields();
+    let generics = struct_options.get_generics(None);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let derive_clone = if struct_options.derives_clone() {
+        quote! {}
+    } else {
+        quote! {#[derive(Clone)]}
+    };
+    let struct_fields_iter = struct_fields.iter();
+    let struct_fields_gen = iface_options.get_struct_fields();
     let mut result = quote! {
-        #[derive(Clone)]
-        #struct_visbility struct #struct_name<FuncType> {
-            #struct_fields
+        #(#struct_attrs)*
+        #derive_clone
+        #struct_visbility struct #struct_name #generics #where_clause {
+            #(#struct_fields_iter,)*
+            #struct_fields_gen
         }
     };
 
+    if let Some(fields_name) = struct_options.get_fields_name() {
+        let mut fields_generics = struct_options.struct_generics.clone();
+        fields_generics
+            .params
+            .insert(0, syn::parse_quote! {'fields});
+        let fields_where_clause = &fields_generics.where_clause;
+        let fields = struct_fields.iter().map(|field| {
+            let syn::Field { vis, ident, ty, .. } = field;
+            quote! {#vis #ident: &'fields #ty}
+        });
+        result.extend(quote! {
+            #[allow(dead_code)]
+            #struct_visbility struct #fields_name #fields_generics #fields_where_clause {
+                #(#fields,)*
+            }
+        });
+    }
+
+    let field_names: Vec<_> = struct_options.get_field_names().collect();
+    let field_types = struct_fields.iter().map(|field| &field.ty);
     let struct_field_inits = iface_options.get_struct_field_inits();
     result.extend(quote! {
-        impl<FuncType> #struct_name<FuncType> {
-            pub fn new(task_func: FuncType) -> Self {
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            pub fn new(task_func: FuncType #(, #field_names: #field_types)*) -> Self {
                 Self {
+                    #(#field_names,)*
                     #struct_field_inits
                 }
             }
         }
     });
 
-    let func_signature = iface_options.get_func_signature();
+    let func_bounds = iface_options.get_func_bounds(struct_options);
+    let generics = struct_options.get_generics(Some(&func_bounds));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-    let mut input_handles = quote! {};
-    let mut input_matches = quote! {};
     let mut get_input_vals = quote! {};
     let mut input_params = quote! {};
-    for i in 0..iface_options.get_num_inputs() {
-        if i > 0 {
-            input_handles.extend(quote! {,});
-            input_matches.extend(quote! {,});
+    if let Some(fields_init) = struct_options.get_fields_init() {
+        input_params.extend(fields_init);
+    }
+    for (i, input) in iface_options.inputs.iter().enumerate() {
+        if !input_params.is_empty() {
             input_params.extend(quote! {,});
         }
 
-        let field_name = quote::format_ident!("input{}_handle", i);
-        input_handles.extend(quote! {&self.#field_name});
-
-        let input_name = quote::format_ident!("input{}", i);
-        input_matches.extend(quote! {Some(#input_name)});
-
         let value_name = quote::format_ident!("input{}_value", i);
-        get_input_vals.extend(quote! {let #value_name = #input_name.get_value(flow);});
-
-        input_params.extend(quote! {#value_name.unwrap()})
+        get_input_vals.extend(input.get_input_value(i));
+        input_params.extend(quote! {#value_name})
     }
 
     let mut output_params = quote! {};
@@ -191,20 +797,53 @@ pub(crate) fn generate_task_struct_impls(
         set_output_vals.extend(quote! {self.#value_name = Some(#value_name);})
     }
 
+    let join_policy_impl = iface_options.get_join_policy_impl();
+
+    let Some(error_type) = &iface_options.error_type else {
+        result.extend(quote! {
+            impl #impl_generics ExecutableTask for #struct_name #ty_generics #where_clause {
+                fn exec(&mut self, flow: &Flow) {
+                    #get_input_vals
+                    let (#output_params) = (self.func)(#input_params);
+                    #set_output_vals
+                }
+
+                #join_policy_impl
+            }
+        });
+        return result;
+    };
+
     result.extend(quote! {
-        impl<FuncType: 'static + Clone + Send + Sync + #func_signature> ExecutableTask for #struct_name<FuncType> {
+        impl #impl_generics ExecutableTask for #struct_name #ty_generics #where_clause {
             fn exec(&mut self, flow: &Flow) {
-                match (#input_handles) {
-                    (#input_matches) => {
-                        #get_input_vals
-                        let (#output_params) = (self.func)(#input_params);
+                self.error = None;
+                #get_input_vals
+                match (self.func)(#input_params) {
+                    Ok((#output_params)) => {
                         #set_output_vals
                     }
-                    _ => {
-                        unreachable!();
-                    }
+                    Err(error) => self.error = Some(std::sync::Arc::new(error)),
                 }
             }
+
+            fn get_error_message(&self) -> Option<String> {
+                self.error.as_ref().map(|error| error.to_string())
+            }
+
+            #join_policy_impl
+        }
+
+        impl #impl_generics FallibleTask for #struct_name #ty_generics #where_clause {
+            type Error = #error_type;
+
+            fn get_error(task: &dyn ExecutableTask) -> Option<&Self::Error> {
+                task.as_any()
+                    .downcast_ref::<Self>()
+                    .unwrap()
+                    .error
+                    .as_deref()
+            }
         }
     });
 
@@ -216,34 +855,31 @@ pub(crate) fn generate_task_input_impl(
     iface_options: &TaskInterfaceOptions,
     index: usize,
 ) -> proc_macro2::TokenStream {
-    let TaskStructOptions {
-        struct_name,
-        struct_visibility: _,
-    } = struct_options;
-    let TaskInterfaceOptions {
-        input_types,
-        output_types: _,
-    } = iface_options;
+    let struct_name = &struct_options.struct_name;
+    let inputs = &iface_options.inputs;
 
-    let func_signature = iface_options.get_func_signature();
+    let func_bounds = iface_options.get_func_bounds(struct_options);
+    let generics = struct_options.get_generics(Some(&func_bounds));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     let trait_name = quote::format_ident!("TaskInput{}", index);
     let method_name = quote::format_ident!("set_input_{}", index);
-    let input_type = input_types.get(index).unwrap();
-    let field_name = quote::format_ident!("input{}_handle", index);
+    let input = inputs.get(index).unwrap();
+    let input_type = &input.port_type;
+    let set_input = input.get_set_input(index);
 
     let mut trait_params = quote! {};
     for i in 0..=index {
         if i > 0 {
             trait_params.extend(quote! {,})
         }
-        let param_type = input_types.get(i).unwrap();
+        let param_type = &inputs.get(i).unwrap().port_type;
         trait_params.extend(quote! {#param_type})
     }
 
     quote! {
-        impl<FuncType: 'static + Clone + Send + Sync + #func_signature> #trait_name<#trait_params> for #struct_name<FuncType> {
+        impl #impl_generics #trait_name<#trait_params> for #struct_name #ty_generics #where_clause {
             fn #method_name(&mut self, task_input: TaskInputHandle<#input_type>) {
-                self.#field_name = Some(task_input);
+                #set_input
             }
         }
     }
@@ -254,16 +890,12 @@ pub(crate) fn generate_task_output_impl(
     iface_options: &TaskInterfaceOptions,
     index: usize,
 ) -> proc_macro2::TokenStream {
-    let TaskStructOptions {
-        struct_name,
-        struct_visibility: _,
-    } = struct_options;
-    let TaskInterfaceOptions {
-        input_types: _,
-        output_types,
-    } = iface_options;
+    let struct_name = &struct_options.struct_name;
+    let output_types = &iface_options.output_types;
 
-    let func_signature = iface_options.get_func_signature();
+    let func_bounds = iface_options.get_func_bounds(struct_options);
+    let generics = struct_options.get_generics(Some(&func_bounds));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     let trait_name = quote::format_ident!("TaskOutput{}", index);
     let method_name = quote::format_ident!("get_output_{}", index);
     let output_type = output_types.get(index).unwrap();
@@ -279,7 +911,7 @@ pub(crate) fn generate_task_output_impl(
     }
 
     quote! {
-        impl<FuncType: 'static + Clone + Send + Sync + #func_signature> #trait_name<#trait_params> for #struct_name<FuncType> {
+        impl #impl_generics #trait_name<#trait_params> for #struct_name #ty_generics #where_clause {
             fn #method_name(task: &dyn ExecutableTask) -> Option<&#output_type> {
                 task.as_any()
                     .downcast_ref::<Self>()
@@ -290,3 +922,163 @@ pub(crate) fn generate_task_output_impl(
         }
     }
 }
+
+// the `ErasedPorts` impl, connecting the ports by number with their types checked at run time
+pub(crate) fn generate_erased_ports_impl(
+    struct_options: &TaskStructOptions,
+    iface_options: &TaskInterfaceOptions,
+) -> proc_macro2::TokenStream {
+    let struct_name = &struct_options.struct_name;
+    let inputs = &iface_options.inputs;
+    let output_types = &iface_options.output_types;
+
+    let func_bounds = iface_options.get_func_bounds(struct_options);
+    let generics = struct_options.get_generics(Some(&func_bounds));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut output_arms = quote! {};
+    for (i, output_type) in output_types.iter().enumerate() {
+        let trait_name = quote::format_ident!("TaskOutput{}", i);
+        let method_name = quote::format_ident!("get_output_{}", i);
+        let trait_params = output_types.iter().take(i + 1);
+        output_arms.extend(quote! {
+            #i => Some(ErasedOutput::new::<#output_type>(
+                task_id,
+                port,
+                <Self as #trait_name<#(#trait_params),*>>::#method_name,
+            )),
+        });
+    }
+
+    let mut input_arms = quote! {};
+    for (i, input) in inputs.iter().enumerate() {
+        let input_type = &input.port_type;
+        let set_input = input.get_set_input(i);
+        input_arms.extend(quote! {
+            #i => match output.get_input_handle::<#input_type>() {
+                Some(task_input) => {
+                    #set_input
+                    true
+                }
+                None => false,
+            },
+        });
+    }
+
+    quote! {
+        impl #impl_generics ErasedPorts for #struct_name #ty_generics #where_clause {
+            fn get_erased_output(task_id: usize, port: usize) -> Option<ErasedOutput> {
+                match port {
+                    #output_arms
+                    _ => None,
+                }
+            }
+
+            fn set_erased_input(&mut self, port: usize, output: &ErasedOutput) -> bool {
+                match port {
+                    #input_arms
+                    _ => false,
+                }
+            }
+        }
+    }
+}
+
+//
+// For tasks with named ports, structs holding the named inputs and outputs and the `TaskPorts`
+// impl handing them out, e.g. `TaskCInputs` and `TaskCOutputs` for a `TaskC`.
+//
+pub(crate) fn generate_task_ports_impl(
+    struct_options: &TaskStructOptions,
+    iface_options: &TaskInterfaceOptions,
+) -> proc_macro2::TokenStream {
+    let struct_name = &struct_options.struct_name;
+    let struct_visibility = &struct_options.struct_visibility;
+    let TaskInterfaceOptions {
+        inputs,
+        output_types,
+        output_names,
+        error_type: _,
+    } = iface_options;
+
+    if inputs.iter().all(|input| input.name.is_none())
+        && output_names.iter().all(|name| name.is_none())
+    {
+        return quote! {};
+    }
+
+    let func_bounds = iface_options.get_func_bounds(struct_options);
+    let generics = struct_options.get_generics(Some(&func_bounds));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let ports_generics = struct_options.get_generics(None);
+    let inputs_name = quote::format_ident!("{}Inputs", struct_name);
+    let outputs_name = quote::format_ident!("{}Outputs", struct_name);
+
+    let mut input_fields = quote! {};
+    let mut input_inits = quote! {};
+    for (i, input) in inputs.iter().enumerate() {
+        if let Some(name) = &input.name {
+            let input_type = &input.port_type;
+            let trait_name = quote::format_ident!("TaskInput{}", i);
+            let method_name = quote::format_ident!("set_input_{}", i);
+            let trait_params = inputs.iter().take(i + 1).map(|input| &input.port_type);
+            input_fields.extend(quote! {
+                pub #name: InputPort<#struct_name #ty_generics, #input_type>,
+            });
+            input_inits.extend(quote! {
+                #name: InputPort::new(task_id, #i, <Self as #trait_name<#(#trait_params),*>>::#method_name),
+            });
+        }
+    }
+
+    let mut output_fields = quote! {};
+    let mut output_inits = quote! {};
+    for (i, (name, output_type)) in output_names.iter().zip(output_types.iter()).enumerate() {
+        if let Some(name) = name {
+            let trait_name = quote::format_ident!("TaskOutput{}", i);
+            let method_name = quote::format_ident!("get_output_{}", i);
+            let trait_params = output_types.iter().take(i + 1);
+            output_fields.extend(quote! {
+                pub #name: OutputPort<#output_type>,
+            });
+            output_inits.extend(quote! {
+                #name: OutputPort::new(task_id, #i, <Self as #trait_name<#(#trait_params),*>>::#method_name),
+            });
+        }
+    }
+
+    quote! {
+        #[allow(dead_code)]
+        #struct_visibility struct #inputs_name #ports_generics #where_clause {
+            #input_fields
+            _task: std::marker::PhantomData<fn() -> #struct_name #ty_generics>,
+        }
+
+        #[allow(dead_code)]
+        #struct_visibility struct #outputs_name #ports_generics #where_clause {
+            #output_fields
+            _task: std::marker::PhantomData<fn() -> #struct_name #ty_generics>,
+        }
+
+        impl #impl_generics TaskPorts for #struct_name #ty_generics #where_clause {
+            type Inputs = #inputs_name #ty_generics;
+            type Outputs = #outputs_name #ty_generics;
+
+            #[allow(unused_variables)]
+            fn get_inputs(task_id: usize) -> Self::Inputs {
+                #inputs_name {
+                    #input_inits
+                    _task: std::marker::PhantomData,
+                }
+            }
+
+            #[allow(unused_variables)]
+            fn get_outputs(task_id: usize) -> Self::Outputs {
+                #outputs_name {
+                    #output_inits
+                    _task: std::marker::PhantomData,
+                }
+            }
+        }
+    }
+}
@@ -19,6 +19,9 @@ impl Parse for TaskFlowOptions {
     fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
         let num_lit = input.parse::<syn::LitInt>()?;
         let num = num_lit.base10_parse::<usize>()?;
+        if !input.is_empty() {
+            return Err(input.error("expected only the number of ports"));
+        }
         Ok(TaskFlowOptions { num_ports: num })
     }
 }
@@ -63,7 +66,14 @@ pub(crate) fn generate_connect_tasks_func(
             task1_handle: &TaskHandle<A>,
             task2_handle: &TaskHandle<B>,
         ) {
-            self.connect(task1_handle, A::#output_func_name, task2_handle, B::#input_func_name);
+            self.connect(
+                task1_handle,
+                #task1_oport_num,
+                A::#output_func_name,
+                task2_handle,
+                #task2_iport_num,
+                B::#input_func_name,
+            );
         }
     }
 }
@@ -27,6 +27,9 @@ impl Parse for TaskTraitOptions {
 
         let n_lit = input.parse::<syn::LitInt>()?;
         let n = n_lit.base10_parse::<usize>()?;
+        if !input.is_empty() {
+            return Err(input.error("expected a trait name, a function name and a count"));
+        }
 
         Ok(TaskTraitOptions {
             trait_name: ident1,
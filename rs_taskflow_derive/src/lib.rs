@@ -2,6 +2,7 @@ mod derive_task_helper;
 mod gen_connect_tasks_helper;
 mod gen_task_output_helper;
 mod gen_task_traits_helper;
+mod task_fn_helper;
 
 use quote::quote;
 
@@ -73,6 +74,19 @@ pub fn generate_get_task_output_funcs(input: proc_macro::TokenStream) -> proc_ma
     result.into()
 }
 
+#[proc_macro]
+pub fn generate_record_output_funcs(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let options = syn::parse_macro_input!(input as gen_connect_tasks_helper::TaskFlowOptions);
+
+    let mut result = quote! {};
+    for i in 0..options.get_num_ports() {
+        let func = gen_task_output_helper::generate_record_output_func(i);
+        result.extend(func);
+    }
+
+    result.into()
+}
+
 #[proc_macro_attribute]
 pub fn derive_task(
     args: proc_macro::TokenStream,
@@ -81,26 +95,22 @@ pub fn derive_task(
     let struct_options = syn::parse_macro_input!(input as derive_task_helper::TaskStructOptions);
     let iface_options = syn::parse_macro_input!(args as derive_task_helper::TaskInterfaceOptions);
 
-    let mut result = quote! {};
-    result.extend(derive_task_helper::generate_task_struct_impls(
-        &struct_options,
-        &iface_options,
-    ));
-    for i in 0..iface_options.get_num_inputs() {
-        result.extend(derive_task_helper::generate_task_input_impl(
-            &struct_options,
-            &iface_options,
-            i,
-        ));
-    }
-    for i in 0..iface_options.get_num_outputs() {
-        result.extend(derive_task_helper::generate_task_output_impl(
-            &struct_options,
-            &iface_options,
-            i,
-        ));
+    match derive_task_helper::generate_task(&struct_options, &iface_options) {
+        Ok(result) => result.into(),
+        Err(error) => error.to_compile_error().into(),
     }
+}
 
-    // println!("output: {}", result);
-    result.into()
+#[proc_macro_attribute]
+pub fn task(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let task_fn = syn::parse_macro_input!(input as syn::ItemFn);
+    let task_options = syn::parse_macro_input!(args as task_fn_helper::TaskFnOptions);
+
+    match task_fn_helper::generate_task_fn(&task_options, task_fn) {
+        Ok(result) => result.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
 }
@@ -0,0 +1,329 @@
+extern crate proc_macro2;
+extern crate quote;
+extern crate syn;
+
+use quote::quote;
+use syn::parse::Parse;
+
+use crate::derive_task_helper::{
+    TaskInputJoin, TaskInputKind, TaskInputPort, TaskInterfaceOptions, TaskStructOptions,
+};
+
+pub(crate) struct TaskFnOptions {
+    struct_name: Option<syn::Ident>,
+    error_type: Option<syn::Type>,
+}
+
+//
+// `#[task]`, `#[task(Name)]`, `#[task(error = E)]` or `#[task(Name, error = E)]`, the error type
+// being needed for a `Result` alias that only takes the `T`, such as `io::Result<T>`.
+//
+impl Parse for TaskFnOptions {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        let mut options = TaskFnOptions {
+            struct_name: None,
+            error_type: None,
+        };
+        if input.is_empty() {
+            return Ok(options);
+        }
+        if !input.peek2(syn::Token![=]) {
+            options.struct_name = Some(input.parse::<syn::Ident>()?);
+            if input.is_empty() {
+                return Ok(options);
+            }
+            input.parse::<syn::Token![,]>()?;
+        }
+
+        let expected = "expected the name of the task struct or `error = Type`";
+        if !input.peek(syn::Ident) {
+            return Err(input.error(expected));
+        }
+        let key = input.parse::<syn::Ident>()?;
+        if key != "error" {
+            return Err(syn::Error::new_spanned(key, expected));
+        }
+        input.parse::<syn::Token![=]>()?;
+        options.error_type = Some(input.parse::<syn::Type>()?);
+        input.parse::<Option<syn::Token![,]>>()?;
+        if !input.is_empty() {
+            return Err(input.error("unexpected argument after `error = Type`"));
+        }
+        Ok(options)
+    }
+}
+
+// `add_one` becomes `AddOneTask`
+fn get_default_struct_name(fn_name: &syn::Ident) -> syn::Ident {
+    let mut struct_name = String::new();
+    for word in fn_name.to_string().split('_') {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            struct_name.extend(first.to_uppercase());
+            struct_name.push_str(chars.as_str());
+        }
+    }
+    quote::format_ident!("{}Task", struct_name)
+}
+
+// the `T` of a `Wrapper<&T>` parameter type, e.g. of an `Option<&T>`
+fn get_wrapped_reference<'a>(param_type: &'a syn::Type, wrapper: &str) -> Option<&'a syn::Type> {
+    let syn::Type::Path(type_path) = param_type else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first() {
+        Some(syn::GenericArgument::Type(syn::Type::Reference(reference)))
+            if args.args.len() == 1 =>
+        {
+            Some(reference.elem.as_ref())
+        }
+        _ => None,
+    }
+}
+
+//
+// Turns a parameter into an input port named after it. `&T` is a required input, `Option<&T>` an
+// optional one and `Vec<&T>` collects every producer. `#[default(expr)]` gives a `&T` input a
+// default value, `#[join(first_completed)]` lets it run on the first producer that completes and
+// `#[join(unique)]` rejects a second producer instead of replacing the first.
+//
+fn parse_input(param: &mut syn::PatType) -> syn::Result<TaskInputPort> {
+    let name = match param.pat.as_ref() {
+        syn::Pat::Ident(pat_ident) => pat_ident.ident.clone(),
+        pat => {
+            return Err(syn::Error::new_spanned(
+                pat,
+                "task function parameters must be plain names",
+            ))
+        }
+    };
+
+    let mut default_value = None;
+    let mut join = TaskInputJoin::Replace;
+    for attr in param.attrs.drain(..) {
+        if attr.path().is_ident("default") {
+            default_value = Some(attr.parse_args::<syn::Expr>()?);
+        } else if attr.path().is_ident("join") {
+            let policy = attr.parse_args::<syn::Ident>()?;
+            join = if policy == "unique" {
+                TaskInputJoin::Unique
+            } else if policy == "first_completed" {
+                TaskInputJoin::FirstCompleted
+            } else {
+                return Err(syn::Error::new_spanned(
+                    policy,
+                    "unknown join policy, expected `unique` or `first_completed` (take a `Vec<&T>` to collect)",
+                ));
+            };
+        } else {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "unknown parameter attribute, expected `#[default(...)]` or `#[join(...)]`",
+            ));
+        }
+    }
+
+    let param_type = param.ty.as_ref();
+    let (port_type, kind, join) =
+        if let Some(port_type) = get_wrapped_reference(param_type, "Option") {
+            (port_type, TaskInputKind::Optional, join)
+        } else if let Some(port_type) = get_wrapped_reference(param_type, "Vec") {
+            if join != TaskInputJoin::Replace {
+                return Err(syn::Error::new_spanned(
+                    param_type,
+                    "a collecting `Vec<&T>` parameter cannot have another join policy",
+                ));
+            }
+            (port_type, TaskInputKind::Required, TaskInputJoin::Collect)
+        } else if let syn::Type::Reference(reference) = param_type {
+            if reference.mutability.is_some() {
+                return Err(syn::Error::new_spanned(
+                    reference,
+                    "task function parameters must be shared references",
+                ));
+            }
+            (reference.elem.as_ref(), TaskInputKind::Required, join)
+        } else {
+            return Err(syn::Error::new_spanned(
+                param_type,
+                "expected a parameter of type `&T`, `Option<&T>` or `Vec<&T>`",
+            ));
+        };
+
+    let kind = match (kind, default_value) {
+        (kind, None) => kind,
+        (TaskInputKind::Required, Some(_)) if join == TaskInputJoin::Collect => {
+            return Err(syn::Error::new_spanned(
+                param_type,
+                "a collecting `Vec<&T>` parameter cannot have a default value",
+            ))
+        }
+        (TaskInputKind::Required, Some(default_value)) => TaskInputKind::Default(default_value),
+        (_, Some(_)) => {
+            return Err(syn::Error::new_spanned(
+                param_type,
+                "an optional `Option<&T>` parameter cannot also have a default value",
+            ))
+        }
+    };
+
+    Ok(TaskInputPort::new(
+        Some(name),
+        port_type.clone(),
+        kind,
+        join,
+    ))
+}
+
+// a tuple gives one output per element, any other type a single output
+fn parse_output_types(output_type: &syn::Type) -> syn::Result<Vec<syn::Type>> {
+    match output_type {
+        syn::Type::Tuple(tuple) if tuple.elems.len() == 1 && tuple.elems.trailing_punct() => {
+            Err(syn::Error::new_spanned(
+                tuple,
+                "return the value itself instead of a one-element tuple",
+            ))
+        }
+        syn::Type::Tuple(tuple) => Ok(tuple.elems.iter().cloned().collect()),
+        output_type => Ok(vec![output_type.clone()]),
+    }
+}
+
+// the type arguments of a `Result` return type, e.g. the `T` and `E` of a `Result<T, E>`
+fn get_result_args(return_type: &syn::Type) -> Option<&syn::AngleBracketedGenericArguments> {
+    let syn::Type::Path(type_path) = return_type else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Result" {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => Some(args),
+        _ => None,
+    }
+}
+
+//
+// The outputs are the elements of the returned tuple. A function returning a `Result<T, E>` is a
+// fallible task whose outputs come from the `T`. A `Result` taking only the `T` needs its error
+// type given as `#[task(error = E)]`, which also overrides the `E` of a `Result<T, E>`.
+//
+fn parse_outputs(
+    output: &syn::ReturnType,
+    error_type: Option<&syn::Type>,
+) -> syn::Result<(Vec<syn::Type>, Option<syn::Type>)> {
+    let return_type = match output {
+        syn::ReturnType::Default => None,
+        syn::ReturnType::Type(_, return_type) => Some(return_type.as_ref()),
+    };
+    let Some(args) = return_type.and_then(get_result_args) else {
+        if let Some(error_type) = error_type {
+            return Err(syn::Error::new_spanned(
+                error_type,
+                "`error = Type` needs a task function returning a `Result`",
+            ));
+        }
+        return match return_type {
+            Some(return_type) => Ok((parse_output_types(return_type)?, None)),
+            None => Ok((Vec::new(), None)),
+        };
+    };
+
+    match (
+        args.args.first(),
+        args.args.get(1),
+        args.args.len(),
+        error_type,
+    ) {
+        (Some(syn::GenericArgument::Type(ok_type)), _, 1 | 2, Some(error_type)) => {
+            Ok((parse_output_types(ok_type)?, Some(error_type.clone())))
+        }
+        (
+            Some(syn::GenericArgument::Type(ok_type)),
+            Some(syn::GenericArgument::Type(error_type)),
+            2,
+            None,
+        ) => Ok((parse_output_types(ok_type)?, Some(error_type.clone()))),
+        (_, _, 1, None) => Err(syn::Error::new_spanned(
+            output,
+            "cannot tell the error type of a `Result` with one argument, add `#[task(error = E)]`",
+        )),
+        _ => Err(syn::Error::new_spanned(
+            output,
+            "expected a `Result<T, E>`, or a `Result<T>` with `#[task(error = E)]`",
+        )),
+    }
+}
+
+//
+// Keeps the function as it is, minus the parameter attributes, and generates the task struct
+// `derive_task` would for its ports, with a `Default` impl that runs the function.
+//
+pub(crate) fn generate_task_fn(
+    options: &TaskFnOptions,
+    mut task_fn: syn::ItemFn,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let signature = &task_fn.sig;
+    if !signature.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &signature.generics,
+            "task functions cannot be generic",
+        ));
+    }
+    if let Some(asyncness) = &signature.asyncness {
+        return Err(syn::Error::new_spanned(
+            asyncness,
+            "task functions cannot be async",
+        ));
+    }
+
+    let mut inputs = Vec::new();
+    let mut param_types = Vec::new();
+    for param in task_fn.sig.inputs.iter_mut() {
+        match param {
+            syn::FnArg::Typed(param) => {
+                inputs.push(parse_input(param)?);
+                param_types.push(param.ty.as_ref().clone());
+            }
+            syn::FnArg::Receiver(receiver) => {
+                return Err(syn::Error::new_spanned(
+                    receiver,
+                    "task functions cannot take `self`",
+                ))
+            }
+        }
+    }
+    let (outputs, error_type) = parse_outputs(&task_fn.sig.output, options.error_type.as_ref())?;
+
+    let fn_name = &task_fn.sig.ident;
+    let struct_name = match &options.struct_name {
+        Some(struct_name) => struct_name.clone(),
+        None => get_default_struct_name(fn_name),
+    };
+    let struct_options = TaskStructOptions::new(struct_name.clone(), task_fn.vis.clone());
+    let iface_options = TaskInterfaceOptions::new(inputs, outputs, error_type)?;
+    let return_type = &task_fn.sig.output;
+    let fn_type = quote! {fn(#(#param_types),*) #return_type};
+
+    let mut result = quote! {#task_fn};
+    result.extend(crate::derive_task_helper::generate_task(
+        &struct_options,
+        &iface_options,
+    )?);
+    result.extend(quote! {
+        impl Default for #struct_name<#fn_type> {
+            fn default() -> Self {
+                Self::new(#fn_name as #fn_type)
+            }
+        }
+    });
+    Ok(result)
+}